@@ -8,13 +8,18 @@ mod error;
 mod hash;
 #[cfg(feature = "proof")]
 mod proof;
+#[cfg(all(feature = "hash", feature = "impl-rlp"))]
+mod trie;
 pub mod types;
 
 pub use error::Error;
 
 #[cfg(feature = "proof")]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "proof")))]
-pub use proof::verify_proof;
+pub use proof::{verify_proof, verify_proof_by_proposal, verify_proof_with_quorum, QuorumRule};
+#[cfg(all(feature = "hash", feature = "impl-rlp"))]
+#[cfg_attr(doc_cfg, doc(cfg(all(feature = "hash", feature = "impl-rlp"))))]
+pub use trie::{verify_trie_multiproof, verify_trie_proof};
 
 #[cfg(feature = "hash")]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "hash")))]
@@ -5,71 +5,72 @@ use blst::min_pk::{AggregatePublicKey, PublicKey, Signature};
 use blst::BLST_ERROR;
 use bytes::Bytes;
 use ethereum_types::H256;
-use rlp::Encodable;
 
 use crate::types::{AxonBlock, Proof, Proposal, ValidatorExtend, Vote};
 use crate::{error::Error, keccak_256};
 
 const DST: &str = "BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RONUL";
 
-// pub fn verify_proof_by_proposal(
-//     proposal: Proposal,
-//     validator_list: &mut [Validator],
-//     proof: Proof,
-// ) -> Result<(), Error> {
-//     let raw_proposal = proposal.rlp_bytes();
-
-//     if keccak_256(&raw_proposal) != proof.block_hash.0 {
-//         return Err(Error::InvalidProofBlockHash);
-//     }
-
-//     let vote = Vote {
-//         height:     proof.number,
-//         round:      proof.round,
-//         vote_type:  2u8,
-//         block_hash: Bytes::from(proof.block_hash.0.to_vec()),
-//     };
-
-//     let hash_vote = keccak_256(rlp::encode(&vote).as_ref());
-//     let pks = extract_pks(&proof, validator_list)?;
-//     let pks = pks.iter().collect::<Vec<_>>();
-//     let c_pk = PublicKey::from_aggregate(&AggregatePublicKey::aggregate(&pks,
-// true)?);     let sig = Signature::from_bytes(&proof.signature)?;
-//     let res = sig.verify(true, &hash_vote, DST.as_bytes(), &[], &c_pk, true);
-
-//     if res == BLST_ERROR::BLST_SUCCESS {
-//         return Ok(());
-//     }
-
-//     Err(res.into())
-// }
+/// The rule used to decide whether the signers of a proof reach the quorum.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QuorumRule {
+    /// The sum of the signers' `vote_weight` must be more than 2/3 of the
+    /// total vote weight of the validator set.
+    #[default]
+    VoteWeight,
+    /// The number of signers must be more than 2/3 of the validator count,
+    /// regardless of their vote weight.
+    SignerCount,
+}
 
+/// Verify the proof of a block with the vote weight based quorum rule.
 pub fn verify_proof(
     block: AxonBlock,
     previous_state_root: H256,
     validator_list: &mut [ValidatorExtend],
     proof: Proof,
 ) -> Result<(), Error> {
-    let raw_proposal = Proposal {
-        version:                  block.header.version,
-        prev_hash:                block.header.prev_hash,
-        proposer:                 block.header.proposer,
-        prev_state_root:          previous_state_root,
-        transactions_root:        block.header.transactions_root,
-        signed_txs_hash:          block.header.signed_txs_hash,
-        timestamp:                block.header.timestamp,
-        number:                   block.header.number,
-        gas_limit:                block.header.gas_limit,
-        extra_data:               block.header.extra_data,
-        base_fee_per_gas:         block.header.base_fee_per_gas,
-        proof:                    block.header.proof,
-        chain_id:                 block.header.chain_id,
-        call_system_script_count: block.header.call_system_script_count,
-        tx_hashes:                block.tx_hashes,
-    }
-    .rlp_bytes();
+    verify_proof_with_quorum(
+        block,
+        previous_state_root,
+        validator_list,
+        proof,
+        QuorumRule::VoteWeight,
+    )
+}
+
+/// Verify the proof of a block, deciding the quorum with the given rule.
+pub fn verify_proof_with_quorum(
+    block: AxonBlock,
+    previous_state_root: H256,
+    validator_list: &mut [ValidatorExtend],
+    proof: Proof,
+    quorum: QuorumRule,
+) -> Result<(), Error> {
+    let proposal = Proposal::from((block, previous_state_root));
 
-    if keccak_256(&raw_proposal) != proof.block_hash.0 {
+    verify_signature(&proposal, validator_list, proof, quorum)
+}
+
+/// Verify the proof of a proposal with the vote weight based quorum rule.
+///
+/// The proposal already carries the previous state root and the transaction
+/// hashes, so no `AxonBlock` is needed.
+pub fn verify_proof_by_proposal(
+    proposal: &Proposal,
+    validator_list: &mut [ValidatorExtend],
+    proof: Proof,
+) -> Result<(), Error> {
+    verify_signature(proposal, validator_list, proof, QuorumRule::VoteWeight)
+}
+
+fn verify_signature(
+    proposal: &Proposal,
+    validator_list: &mut [ValidatorExtend],
+    proof: Proof,
+    quorum: QuorumRule,
+) -> Result<(), Error> {
+    if proposal.hash() != proof.block_hash {
         return Err(Error::InvalidProofBlockHash);
     }
 
@@ -81,7 +82,7 @@ pub fn verify_proof(
     };
 
     let hash_vote = keccak_256(rlp::encode(&vote).as_ref());
-    let pks = extract_pks(&proof, validator_list)?;
+    let pks = extract_pks(&proof, validator_list, quorum)?;
     let pks = pks.iter().collect::<Vec<_>>();
     let c_pk = PublicKey::from_aggregate(&AggregatePublicKey::aggregate(&pks, true)?);
     let sig = Signature::from_bytes(&proof.signature)?;
@@ -97,12 +98,18 @@ pub fn verify_proof(
 fn extract_pks(
     proof: &Proof,
     validator_list: &mut [ValidatorExtend],
+    quorum: QuorumRule,
 ) -> Result<Vec<PublicKey>, Error> {
     validator_list.sort();
 
     let bit_map = BitVec::from_bytes(&proof.bitmap);
     let mut pks = Vec::with_capacity(validator_list.len());
-    let mut count = 0usize;
+    let mut count = 0u64;
+    let mut signed_weight = 0u64;
+    let total_weight = validator_list
+        .iter()
+        .map(|v| u64::from(v.vote_weight))
+        .sum::<u64>();
 
     for (v, bit) in validator_list.iter().zip(bit_map.iter()) {
         if !bit {
@@ -111,11 +118,151 @@ fn extract_pks(
 
         pks.push(PublicKey::from_bytes(&v.bls_pub_key.as_bytes())?);
         count += 1;
+        signed_weight += u64::from(v.vote_weight);
     }
 
-    if count * 3 <= validator_list.len() * 2 {
+    let (signed, total) = match quorum {
+        QuorumRule::VoteWeight => (signed_weight, total_weight),
+        QuorumRule::SignerCount => (count, validator_list.len() as u64),
+    };
+
+    // Widen to u128 so that neither side of the comparison can overflow.
+    if u128::from(signed) * 3 <= u128::from(total) * 2 {
         return Err(Error::NotEnoughSignatures);
     }
 
     Ok(pks)
 }
+
+#[cfg(test)]
+mod tests {
+    use blst::min_pk::{AggregateSignature, SecretKey};
+    use ethereum_types::H160;
+
+    use crate::types::tests::{fixture_block, fixture_proof, fixture_validators, hash};
+
+    use super::*;
+
+    fn validator(seed: u8, vote_weight: u32) -> (SecretKey, ValidatorExtend) {
+        let sk = SecretKey::key_gen(&[seed; 32], &[]).unwrap();
+        let validator = ValidatorExtend {
+            bls_pub_key: sk.sk_to_pk().to_bytes().to_vec().into(),
+            pub_key: alloc::vec![seed; 33].into(),
+            address: H160::from([seed; 20]),
+            vote_weight,
+            ..Default::default()
+        };
+        (sk, validator)
+    }
+
+    /// Sign the proposal hash with the secret keys of the validators in
+    /// `signed`, which are ordered the same as `validators` sorted.
+    fn sign(validators: &[(SecretKey, ValidatorExtend)], signed: &[bool], hash: H256) -> Proof {
+        let vote = Vote {
+            height:     1,
+            round:      0,
+            vote_type:  2u8,
+            block_hash: Bytes::from(hash.0.to_vec()),
+        };
+        let hash_vote = keccak_256(rlp::encode(&vote).as_ref());
+
+        let mut bitmap = BitVec::from_elem(validators.len(), false);
+        let mut signatures = Vec::new();
+        for (idx, ((sk, _), signed)) in validators.iter().zip(signed).enumerate() {
+            if *signed {
+                bitmap.set(idx, true);
+                signatures.push(sk.sign(&hash_vote, DST.as_bytes(), &[]));
+            }
+        }
+        let signatures = signatures.iter().collect::<Vec<_>>();
+        let signature = AggregateSignature::aggregate(&signatures, true)
+            .unwrap()
+            .to_signature();
+
+        Proof {
+            number:     1,
+            round:      0,
+            block_hash: hash,
+            signature:  Bytes::from(signature.to_bytes().to_vec()),
+            bitmap:     Bytes::from(bitmap.to_bytes()),
+        }
+    }
+
+    fn verify(
+        validators: &[(SecretKey, ValidatorExtend)],
+        signed: &[bool],
+        quorum: QuorumRule,
+    ) -> Result<(), Error> {
+        let block = fixture_block();
+        let hash = block.hash(H256::zero());
+        let proof = sign(validators, signed, hash);
+        let mut validator_list = validators
+            .iter()
+            .map(|(_, v)| v.clone())
+            .collect::<Vec<_>>();
+        verify_proof_with_quorum(block, H256::zero(), &mut validator_list, proof, quorum)
+    }
+
+    #[test]
+    fn test_quorum_by_vote_weight() {
+        // Sorted by public key, the first validator holds most of the weight.
+        let validators = alloc::vec![
+            validator(1, 10),
+            validator(2, 1),
+            validator(3, 1),
+            validator(4, 1),
+        ];
+        let majority = [true, false, false, false];
+        let minority = [false, true, true, true];
+
+        assert!(verify(&validators, &majority, QuorumRule::VoteWeight).is_ok());
+        assert!(matches!(
+            verify(&validators, &majority, QuorumRule::SignerCount),
+            Err(Error::NotEnoughSignatures)
+        ));
+        assert!(matches!(
+            verify(&validators, &minority, QuorumRule::VoteWeight),
+            Err(Error::NotEnoughSignatures)
+        ));
+        assert!(verify(&validators, &minority, QuorumRule::SignerCount).is_ok());
+    }
+
+    #[test]
+    fn test_quorum_with_max_weights() {
+        let validators = (1..=4).map(|i| validator(i, u32::MAX)).collect::<Vec<_>>();
+
+        assert!(verify(
+            &validators,
+            &[true, true, true, false],
+            QuorumRule::VoteWeight
+        )
+        .is_ok());
+        assert!(matches!(
+            verify(
+                &validators,
+                &[true, true, false, false],
+                QuorumRule::VoteWeight
+            ),
+            Err(Error::NotEnoughSignatures)
+        ));
+    }
+
+    #[test]
+    fn test_verify_proof_by_proposal() {
+        let block = fixture_block();
+        let proof = fixture_proof();
+        let mut validators = fixture_validators();
+        let previous_state_root =
+            hash("0x9fc948be2cfb0127e979dc9c7e6d2f4a2890b54e0e81fd69c687303e6b25ddde");
+
+        let mut proposal = Proposal::from((block.clone(), previous_state_root));
+        assert!(verify_proof_by_proposal(&proposal, &mut validators, proof.clone()).is_ok());
+        assert!(verify_proof(block, previous_state_root, &mut validators, proof.clone()).is_ok());
+
+        proposal.timestamp += 1;
+        assert!(matches!(
+            verify_proof_by_proposal(&proposal, &mut validators, proof),
+            Err(Error::InvalidProofBlockHash)
+        ));
+    }
+}
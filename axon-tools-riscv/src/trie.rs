@@ -0,0 +1,358 @@
+use alloc::vec::Vec;
+
+use ethereum_types::H256;
+use rlp::{Prototype, Rlp};
+
+use crate::types::MultiProof;
+use crate::{error::Error, keccak_256};
+
+const HASH_LEN: usize = 32;
+
+/// Verify a Merkle Patricia proof of `key` under `root`, and return the value
+/// stored under it, or `None` if the proof shows that the key is absent.
+///
+/// The proof is the list of RLP encoded nodes on the path from the root, in any
+/// order. Nodes shorter than 32 bytes are embedded in their parent, so only the
+/// root and the nodes of at least 32 bytes are looked up by hash.
+///
+/// A hashed node on the path that is missing from the proof is an error, so the
+/// key is reported absent only when the path diverges or ends on an empty slot.
+/// `axon_tools::verify_trie_proof` follows the same rules.
+pub fn verify_trie_proof<T: AsRef<[u8]>>(
+    root: H256,
+    key: &[u8],
+    proof: &[T],
+) -> Result<Option<Vec<u8>>, Error> {
+    NodeSet::new(root, proof).get(key)
+}
+
+/// Verify the proofs of many keys sharing one node set, and return the value
+/// or the absence of every key in order. Each node is hashed only once.
+pub fn verify_trie_multiproof(
+    root: H256,
+    proof: &MultiProof,
+) -> Result<Vec<Option<Vec<u8>>>, Error> {
+    let nodes = NodeSet::new(root, &proof.nodes);
+    proof.keys.iter().map(|key| nodes.get(key)).collect()
+}
+
+/// The nodes of a proof together with their hashes.
+struct NodeSet<'a, T> {
+    root:   H256,
+    nodes:  &'a [T],
+    hashes: Vec<[u8; HASH_LEN]>,
+}
+
+impl<'a, T: AsRef<[u8]>> NodeSet<'a, T> {
+    fn new(root: H256, nodes: &'a [T]) -> Self {
+        let hashes = nodes.iter().map(|node| keccak_256(node.as_ref())).collect();
+
+        NodeSet {
+            root,
+            nodes,
+            hashes,
+        }
+    }
+
+    fn lookup(&self, hash: &[u8]) -> Option<&'a [u8]> {
+        self.nodes
+            .iter()
+            .zip(self.hashes.iter())
+            .find(|(node, h)| {
+                h[..] == *hash && (node.as_ref().len() >= HASH_LEN || **h == self.root.0)
+            })
+            .map(|(node, _)| node.as_ref())
+    }
+
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+        let mut node = Rlp::new(self.lookup(&self.root.0).ok_or(Error::VerifyMptProof)?);
+        check_node(&node)?;
+
+        let key = Nibbles::Raw(key);
+        let mut pos = 0usize;
+
+        loop {
+            match node.prototype().map_err(|_| Error::VerifyMptProof)? {
+                Prototype::Data(0) => return Ok(None),
+                Prototype::Data(HASH_LEN) => {
+                    let raw = self
+                        .lookup(node.data().map_err(|_| Error::VerifyMptProof)?)
+                        .ok_or(Error::VerifyMptProof)?;
+                    node = Rlp::new(raw);
+                    check_node(&node)?;
+                }
+                Prototype::List(2) => {
+                    let path = node
+                        .at(0)
+                        .and_then(|r| r.data())
+                        .map_err(|_| Error::VerifyMptProof)?;
+                    let (path, is_leaf) = Nibbles::from_compact(path)?;
+
+                    if is_leaf {
+                        if key.len() - pos != path.len() || !key.starts_with_at(pos, &path) {
+                            return Ok(None);
+                        }
+                        let value = node
+                            .at(1)
+                            .and_then(|r| r.data())
+                            .map_err(|_| Error::VerifyMptProof)?;
+                        return Ok(Some(value.to_vec()));
+                    }
+
+                    if !key.starts_with_at(pos, &path) {
+                        return Ok(None);
+                    }
+                    pos += path.len();
+                    node = node.at(1).map_err(|_| Error::VerifyMptProof)?;
+                }
+                Prototype::List(17) => {
+                    if pos == key.len() {
+                        let value = node.at(16).map_err(|_| Error::VerifyMptProof)?;
+                        if value.is_empty() {
+                            return Ok(None);
+                        }
+                        return Ok(Some(
+                            value.data().map_err(|_| Error::VerifyMptProof)?.to_vec(),
+                        ));
+                    }
+
+                    node = node.at(key.at(pos)).map_err(|_| Error::VerifyMptProof)?;
+                    pos += 1;
+                }
+                _ => return Err(Error::VerifyMptProof),
+            }
+        }
+    }
+}
+
+/// Check that a node and the nodes embedded in it are well formed, the same
+/// way `cita_trie` checks a node when it decodes it.
+fn check_node(node: &Rlp) -> Result<(), Error> {
+    match node.prototype().map_err(|_| Error::VerifyMptProof)? {
+        Prototype::Data(0) | Prototype::Data(HASH_LEN) => Ok(()),
+        Prototype::List(2) => {
+            let path = node
+                .at(0)
+                .and_then(|r| r.data())
+                .map_err(|_| Error::VerifyMptProof)?;
+            let (_, is_leaf) = Nibbles::from_compact(path)?;
+            let child = node.at(1).map_err(|_| Error::VerifyMptProof)?;
+
+            if is_leaf {
+                child.data().map_err(|_| Error::VerifyMptProof)?;
+                Ok(())
+            } else {
+                check_node(&child)
+            }
+        }
+        Prototype::List(17) => {
+            for i in 0..16 {
+                check_node(&node.at(i).map_err(|_| Error::VerifyMptProof)?)?;
+            }
+
+            let value = node.at(16).map_err(|_| Error::VerifyMptProof)?;
+            if !value.is_empty() {
+                value.data().map_err(|_| Error::VerifyMptProof)?;
+            }
+            Ok(())
+        }
+        _ => Err(Error::VerifyMptProof),
+    }
+}
+
+/// A borrowed nibble path, without the leaf terminator.
+enum Nibbles<'a> {
+    /// Every byte holds two nibbles.
+    Raw(&'a [u8]),
+    /// The first nibble is the low half of the compact flag byte.
+    Odd(u8, &'a [u8]),
+}
+
+impl<'a> Nibbles<'a> {
+    /// Decode a hex prefix encoded path, and tell whether it is a leaf path.
+    fn from_compact(compact: &'a [u8]) -> Result<(Self, bool), Error> {
+        let (flag, rest) = compact.split_first().ok_or(Error::VerifyMptProof)?;
+        let is_leaf = match flag >> 4 {
+            0x0 | 0x1 => false,
+            0x2 | 0x3 => true,
+            _ => return Err(Error::VerifyMptProof),
+        };
+
+        if flag >> 4 & 1 == 1 {
+            Ok((Nibbles::Odd(flag & 0x0f, rest), is_leaf))
+        } else {
+            Ok((Nibbles::Raw(rest), is_leaf))
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Nibbles::Raw(bytes) => bytes.len() * 2,
+            Nibbles::Odd(_, bytes) => bytes.len() * 2 + 1,
+        }
+    }
+
+    fn at(&self, i: usize) -> usize {
+        let (bytes, i) = match self {
+            Nibbles::Raw(bytes) => (bytes, i),
+            Nibbles::Odd(first, _) if i == 0 => return *first as usize,
+            Nibbles::Odd(_, bytes) => (bytes, i - 1),
+        };
+
+        let byte = bytes[i / 2];
+        if i % 2 == 0 {
+            (byte >> 4) as usize
+        } else {
+            (byte & 0x0f) as usize
+        }
+    }
+
+    /// Whether the nibbles from `pos` on start with `prefix`.
+    fn starts_with_at(&self, pos: usize, prefix: &Nibbles) -> bool {
+        self.len() - pos >= prefix.len()
+            && (0..prefix.len()).all(|i| self.at(pos + i) == prefix.at(i))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::sync::Arc;
+
+    use alloc::vec;
+    use bytes::Bytes;
+    use cita_trie::{MemoryDB, PatriciaTrie, Trie};
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+
+    #[derive(Default)]
+    struct Keccak;
+
+    impl cita_trie::Hasher for Keccak {
+        const LENGTH: usize = HASH_LEN;
+
+        fn digest(&self, data: &[u8]) -> Vec<u8> {
+            keccak_256(data).to_vec()
+        }
+    }
+
+    fn assert_same(root: H256, key: &[u8], proof: Vec<Vec<u8>>) {
+        let expect = cita_trie::verify_proof(&root.0, key, proof.clone(), Keccak).ok();
+        let actual = verify_trie_proof(root, key, &proof).ok();
+        assert_eq!(actual, expect, "key {:?}, proof {:?}", key, proof);
+    }
+
+    /// A tampered proof must fail, or read the same as the honest one.
+    fn assert_not_forged(root: H256, key: &[u8], honest: &Option<Vec<u8>>, proof: Vec<Vec<u8>>) {
+        if let Ok(value) = verify_trie_proof(root, key, &proof) {
+            assert_eq!(value, *honest, "key {:?}, proof {:?}", key, proof);
+        }
+    }
+
+    #[test]
+    fn test_same_as_cita_trie() {
+        let mut rng = StdRng::seed_from_u64(42);
+
+        for round in 0..20 {
+            let mut trie = PatriciaTrie::new(Arc::new(MemoryDB::new(true)), Arc::new(Keccak));
+            let mut keys = Vec::new();
+            for i in 0..rng.gen_range(1..80u64) {
+                // Small indices and values build embedded nodes, hashed keys
+                // build long extension and leaf paths.
+                let key = if round % 2 == 0 {
+                    rlp::encode(&i).to_vec()
+                } else {
+                    keccak_256(&i.to_be_bytes()).to_vec()
+                };
+                let value = (0..rng.gen_range(1..48))
+                    .map(|_| rng.gen())
+                    .collect::<Vec<u8>>();
+                trie.insert(key.clone(), value).unwrap();
+                keys.push(key);
+            }
+            let root = H256::from_slice(&trie.root().unwrap());
+
+            for key in keys
+                .iter()
+                .chain([vec![0xffu8; 3], keccak_256(&[1u8]).to_vec()].iter())
+            {
+                let proof = trie.get_proof(key).unwrap();
+                assert_same(root, key, proof.clone());
+                let honest = verify_trie_proof(root, key, &proof).unwrap();
+
+                for i in 0..proof.len() {
+                    // Every node of an honest proof is on the path.
+                    let mut dropped = proof.clone();
+                    dropped.remove(i);
+                    assert!(verify_trie_proof(root, key, &dropped).is_err());
+
+                    let mut flipped = proof.clone();
+                    let j = rng.gen_range(0..flipped[i].len());
+                    flipped[i][j] ^= 1;
+                    assert_not_forged(root, key, &honest, flipped);
+                }
+
+                let mut reversed = proof;
+                reversed.reverse();
+                assert_same(root, key, reversed);
+            }
+        }
+    }
+
+    #[test]
+    fn test_verify_trie_proof() {
+        let mut trie = PatriciaTrie::new(Arc::new(MemoryDB::new(true)), Arc::new(Keccak));
+        for i in 0..3u64 {
+            trie.insert(rlp::encode(&i).to_vec(), vec![i as u8; 40])
+                .unwrap();
+        }
+        let root = H256::from_slice(&trie.root().unwrap());
+        let proof = trie.get_proof(&rlp::encode(&1u64)).unwrap();
+
+        assert_eq!(
+            verify_trie_proof(root, &rlp::encode(&1u64), &proof).unwrap(),
+            Some(vec![1u8; 40])
+        );
+        assert_eq!(
+            verify_trie_proof(root, &rlp::encode(&7u64), &proof).unwrap(),
+            None
+        );
+        assert!(verify_trie_proof(H256::zero(), &rlp::encode(&1u64), &proof).is_err());
+        assert!(verify_trie_proof::<Vec<u8>>(root, &rlp::encode(&1u64), &[]).is_err());
+    }
+
+    #[test]
+    fn test_verify_trie_multiproof() {
+        let mut trie = PatriciaTrie::new(Arc::new(MemoryDB::new(true)), Arc::new(Keccak));
+        for i in 0..100u64 {
+            trie.insert(rlp::encode(&i).to_vec(), vec![i as u8; 40])
+                .unwrap();
+        }
+        let root = H256::from_slice(&trie.root().unwrap());
+
+        let keys = [3u64, 50, 99, 100]
+            .iter()
+            .map(|i| Bytes::from(rlp::encode(i).to_vec()))
+            .collect::<Vec<_>>();
+        let mut nodes = Vec::<Bytes>::new();
+        for key in keys.iter() {
+            for node in trie.get_proof(key).unwrap() {
+                if !nodes.iter().any(|n| n[..] == node[..]) {
+                    nodes.push(node.into());
+                }
+            }
+        }
+        let proof = MultiProof { keys, nodes };
+
+        let values = verify_trie_multiproof(root, &proof).unwrap();
+        assert_eq!(values, vec![
+            Some(vec![3u8; 40]),
+            Some(vec![50u8; 40]),
+            Some(vec![99u8; 40]),
+            None
+        ]);
+        assert!(verify_trie_multiproof(H256::zero(), &proof).is_err());
+    }
+}
@@ -18,17 +18,30 @@ pub type BlockNumber = u64;
 #[derive(Debug)]
 pub enum TypesError {
     InvalidBlockVersion(u8),
+    InvalidDirection,
 }
 
 // impl std::error::Error for TypesError {}
 
+/// The layout of a block, which decides how the header and the proposal are
+/// encoded and hashed.
+///
+/// New versions are added as Axon ships new layouts, so matching on it needs a
+/// wildcard arm outside this crate.
 #[derive(Default, Copy, Clone, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "impl-serde", derive(serde::Deserialize))]
+#[non_exhaustive]
 pub enum BlockVersion {
     #[default]
     V0,
 }
 
+impl BlockVersion {
+    /// Every supported version, in order.
+    pub const ALL: &'static [BlockVersion] = &[BlockVersion::V0];
+    pub const LATEST: BlockVersion = BlockVersion::V0;
+}
+
 impl From<BlockVersion> for u8 {
     fn from(value: BlockVersion) -> Self {
         match value {
@@ -79,12 +92,88 @@ pub struct ExtraData {
     pub inner: Bytes,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+/// A hardfork of Axon, identified by its bit in `HardforkInfoInner::flags`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum HardforkName {
+    Andromeda,
+}
+
+impl HardforkName {
+    pub fn flag(self) -> H256 {
+        match self {
+            HardforkName::Andromeda => H256::from_low_u64_be(0b1),
+        }
+    }
+}
+
+/// The hardforks enabled from `block_number` on, stored RLP encoded in the
+/// first `extra_data` of a header.
+#[derive(Default, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "impl-rlp",
+    derive(rlp_derive::RlpEncodable, rlp_derive::RlpDecodable)
+)]
+#[cfg_attr(feature = "impl-serde", derive(serde::Deserialize))]
+#[cfg_attr(feature = "impl-serde", serde(rename_all = "camelCase"))]
+pub struct HardforkInfoInner {
+    #[cfg_attr(
+        feature = "impl-serde",
+        serde(deserialize_with = "decode::deserialize_hex_u64")
+    )]
+    pub block_number: BlockNumber,
+    pub flags:        H256,
+}
+
+impl HardforkInfoInner {
+    pub fn contains(&self, name: HardforkName) -> bool {
+        let flag = name.flag();
+        self.flags & flag == flag
+    }
+
+    /// Whether the hardfork is enabled at the block `number`.
+    pub fn is_enabled(&self, name: HardforkName, number: BlockNumber) -> bool {
+        number >= self.block_number && self.contains(name)
+    }
+}
+
+/// The hardfork schedule of a chain, as collected from the headers announcing
+/// the hardforks.
+#[derive(Default, Clone, Debug, PartialEq, Eq)]
 #[cfg_attr(
     feature = "impl-rlp",
     derive(rlp_derive::RlpEncodable, rlp_derive::RlpDecodable)
 )]
 #[cfg_attr(feature = "impl-serde", derive(serde::Deserialize))]
+pub struct HardforkInfo {
+    pub inner: Vec<HardforkInfoInner>,
+}
+
+impl HardforkInfo {
+    /// The first block at which the hardfork is enabled, if it is scheduled.
+    pub fn enable_height(&self, name: HardforkName) -> Option<BlockNumber> {
+        self.inner
+            .iter()
+            .filter(|info| info.contains(name))
+            .map(|info| info.block_number)
+            .min()
+    }
+
+    pub fn is_enabled(&self, name: HardforkName, number: BlockNumber) -> bool {
+        self.enable_height(name)
+            .map(|height| number >= height)
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(feature = "impl-rlp")]
+impl ExtraData {
+    pub fn hardfork_info(&self) -> Result<HardforkInfoInner, DecoderError> {
+        rlp::decode(&self.inner)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "impl-serde", derive(serde::Deserialize))]
 pub struct AxonHeader {
     pub version:                  BlockVersion,
     pub prev_hash:                Hash,
@@ -174,22 +263,191 @@ pub struct Proposal {
 }
 
 #[cfg(feature = "impl-rlp")]
+impl Encodable for AxonHeader {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        match self.version {
+            BlockVersion::V0 => {
+                s.begin_list(17)
+                    .append(&self.version)
+                    .append(&self.prev_hash)
+                    .append(&self.proposer)
+                    .append(&self.state_root)
+                    .append(&self.transactions_root)
+                    .append(&self.signed_txs_hash)
+                    .append(&self.receipts_root)
+                    .append(&self.log_bloom)
+                    .append(&self.timestamp)
+                    .append(&self.number)
+                    .append(&self.gas_used)
+                    .append(&self.gas_limit)
+                    .append_list(&self.extra_data)
+                    .append(&self.base_fee_per_gas)
+                    .append(&self.proof)
+                    .append(&self.call_system_script_count)
+                    .append(&self.chain_id);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "impl-rlp")]
+impl Decodable for AxonHeader {
+    fn decode(r: &Rlp) -> Result<Self, DecoderError> {
+        match r.val_at(0)? {
+            BlockVersion::V0 => {
+                if r.item_count()? != 17 {
+                    return Err(DecoderError::RlpIncorrectListLen);
+                }
+
+                Ok(AxonHeader {
+                    version:                  BlockVersion::V0,
+                    prev_hash:                r.val_at(1)?,
+                    proposer:                 r.val_at(2)?,
+                    state_root:               r.val_at(3)?,
+                    transactions_root:        r.val_at(4)?,
+                    signed_txs_hash:          r.val_at(5)?,
+                    receipts_root:            r.val_at(6)?,
+                    log_bloom:                r.val_at(7)?,
+                    timestamp:                r.val_at(8)?,
+                    number:                   r.val_at(9)?,
+                    gas_used:                 r.val_at(10)?,
+                    gas_limit:                r.val_at(11)?,
+                    extra_data:               r.list_at(12)?,
+                    base_fee_per_gas:         r.val_at(13)?,
+                    proof:                    r.val_at(14)?,
+                    call_system_script_count: r.val_at(15)?,
+                    chain_id:                 r.val_at(16)?,
+                })
+            }
+        }
+    }
+}
+
+#[cfg(all(feature = "proof", feature = "impl-rlp"))]
 impl Encodable for Proposal {
     fn rlp_append(&self, s: &mut RlpStream) {
-        s.begin_list(13)
-            .append(&self.version)
-            .append(&self.prev_hash)
-            .append(&self.proposer)
-            .append(&self.prev_state_root)
-            .append(&self.transactions_root)
-            .append(&self.signed_txs_hash)
-            .append(&self.timestamp)
-            .append(&self.number)
-            .append(&self.gas_limit.as_u64())
-            .append_list(&self.extra_data)
-            .append(&self.proof)
-            .append(&self.call_system_script_count)
-            .append_list(&self.tx_hashes);
+        match self.version {
+            BlockVersion::V0 => {
+                s.begin_list(13)
+                    .append(&self.version)
+                    .append(&self.prev_hash)
+                    .append(&self.proposer)
+                    .append(&self.prev_state_root)
+                    .append(&self.transactions_root)
+                    .append(&self.signed_txs_hash)
+                    .append(&self.timestamp)
+                    .append(&self.number)
+                    .append(&self.gas_limit.as_u64())
+                    .append_list(&self.extra_data)
+                    .append(&self.proof)
+                    .append(&self.call_system_script_count)
+                    .append_list(&self.tx_hashes);
+            }
+        }
+    }
+}
+
+/// The V0 proposal does not carry `base_fee_per_gas` and `chain_id`, which
+/// are decoded as zero.
+#[cfg(all(feature = "proof", feature = "impl-rlp"))]
+impl Decodable for Proposal {
+    fn decode(r: &Rlp) -> Result<Self, DecoderError> {
+        match r.val_at(0)? {
+            BlockVersion::V0 => {
+                if r.item_count()? != 13 {
+                    return Err(DecoderError::RlpIncorrectListLen);
+                }
+
+                Ok(Proposal {
+                    version:                  BlockVersion::V0,
+                    prev_hash:                r.val_at(1)?,
+                    proposer:                 r.val_at(2)?,
+                    prev_state_root:          r.val_at(3)?,
+                    transactions_root:        r.val_at(4)?,
+                    signed_txs_hash:          r.val_at(5)?,
+                    timestamp:                r.val_at(6)?,
+                    number:                   r.val_at(7)?,
+                    gas_limit:                U256::from(r.val_at::<u64>(8)?),
+                    extra_data:               r.list_at(9)?,
+                    base_fee_per_gas:         U256::zero(),
+                    proof:                    r.val_at(10)?,
+                    chain_id:                 0,
+                    call_system_script_count: r.val_at(11)?,
+                    tx_hashes:                r.list_at(12)?,
+                })
+            }
+        }
+    }
+}
+
+#[cfg(all(feature = "hash", feature = "impl-rlp"))]
+#[cfg_attr(doc_cfg, doc(cfg(all(feature = "hash", feature = "impl-rlp"))))]
+impl AxonHeader {
+    /// The keccak hash of the RLP encoded header, which the next block refers
+    /// to by `prev_hash`.
+    pub fn hash(&self) -> Hash {
+        match self.version {
+            BlockVersion::V0 => H256(crate::keccak_256(&rlp::encode(self))),
+        }
+    }
+}
+
+#[cfg(feature = "impl-rlp")]
+impl AxonHeader {
+    /// The hardfork info in the first `extra_data`, or `None` if the header
+    /// has no extra data.
+    pub fn hardfork_info(&self) -> Result<Option<HardforkInfoInner>, DecoderError> {
+        self.extra_data
+            .first()
+            .map(ExtraData::hardfork_info)
+            .transpose()
+    }
+}
+
+#[cfg(feature = "proof")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "proof")))]
+impl AxonBlock {
+    /// The hash of the block proposal, which the validators vote for and
+    /// `Proof::block_hash` holds. It depends on the state root before the
+    /// block is executed.
+    pub fn hash(&self, previous_state_root: H256) -> Hash {
+        Proposal::from((self.clone(), previous_state_root)).hash()
+    }
+}
+
+#[cfg(feature = "proof")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "proof")))]
+impl Proposal {
+    /// The keccak hash of the RLP encoded proposal.
+    pub fn hash(&self) -> Hash {
+        match self.version {
+            BlockVersion::V0 => H256(crate::keccak_256(&self.rlp_bytes())),
+        }
+    }
+}
+
+#[cfg(feature = "proof")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "proof")))]
+impl From<(AxonBlock, H256)> for Proposal {
+    /// Rebuild the proposal of a block with the state root before it.
+    fn from((block, previous_state_root): (AxonBlock, H256)) -> Self {
+        Proposal {
+            version:                  block.header.version,
+            prev_hash:                block.header.prev_hash,
+            proposer:                 block.header.proposer,
+            prev_state_root:          previous_state_root,
+            transactions_root:        block.header.transactions_root,
+            signed_txs_hash:          block.header.signed_txs_hash,
+            timestamp:                block.header.timestamp,
+            number:                   block.header.number,
+            gas_limit:                block.header.gas_limit,
+            extra_data:               block.header.extra_data,
+            base_fee_per_gas:         block.header.base_fee_per_gas,
+            proof:                    block.header.proof,
+            chain_id:                 block.header.chain_id,
+            call_system_script_count: block.header.call_system_script_count,
+            tx_hashes:                block.tx_hashes,
+        }
     }
 }
 
@@ -265,7 +523,7 @@ pub struct Vote {
     pub block_hash: Bytes,
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "proof"))]
 impl Vote {
     fn random() -> Self {
         Self {
@@ -353,9 +611,11 @@ impl PartialOrd for ValidatorExtend {
     }
 }
 
+/// Validators are ordered by public key, which is the order the bitmap of a
+/// proof follows.
 impl Ord for ValidatorExtend {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.address.cmp(&other.address)
+        self.pub_key.cmp(&other.pub_key)
     }
 }
 
@@ -377,14 +637,126 @@ pub struct CkbRelatedInfo {
     pub reward_smt_type_id:   H256,
 }
 
+/// The proofs of many keys of one trie, sharing the nodes on their paths.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "impl-rlp",
+    derive(rlp_derive::RlpEncodable, rlp_derive::RlpDecodable)
+)]
+pub struct MultiProof {
+    pub keys:  Vec<Bytes>,
+    /// The nodes of every single proof, each of them only once.
+    pub nodes: Vec<Bytes>,
+}
+
+/// The direction of a cross-chain transfer between CKB and Axon.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "impl-serde", derive(serde::Deserialize))]
+pub enum Direction {
+    #[default]
+    FromCkb,
+    FromAxon,
+}
+
+impl From<Direction> for u8 {
+    fn from(value: Direction) -> Self {
+        match value {
+            Direction::FromCkb => 0,
+            Direction::FromAxon => 1,
+        }
+    }
+}
+
+impl TryFrom<u8> for Direction {
+    type Error = TypesError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Direction::FromCkb),
+            1 => Ok(Direction::FromAxon),
+            _ => Err(TypesError::InvalidDirection),
+        }
+    }
+}
+
+#[cfg(feature = "impl-rlp")]
+impl Encodable for Direction {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        u8::from(*self).rlp_append(s);
+    }
+}
+
+#[cfg(feature = "impl-rlp")]
+impl Decodable for Direction {
+    fn decode(r: &Rlp) -> Result<Self, DecoderError> {
+        r.as_val::<u8>()?
+            .try_into()
+            .map_err(|_| DecoderError::Custom("Invalid crosschain direction"))
+    }
+}
+
+/// A transfer of CKB and of an sUDT, mapped to an ERC20 token on Axon,
+/// between the two chains.
+///
+/// There is no conversion from the logs of the bridge contracts yet, since
+/// their event signatures and a recorded log to test against are not
+/// available.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "impl-rlp",
+    derive(rlp_derive::RlpEncodable, rlp_derive::RlpDecodable)
+)]
+#[cfg_attr(feature = "impl-serde", derive(serde::Deserialize))]
+#[cfg_attr(feature = "impl-serde", serde(rename_all = "camelCase"))]
+pub struct Transfer {
+    pub direction:      Direction,
+    /// The hash of the transaction which started the transfer on the source
+    /// chain.
+    pub tx_hash:        H256,
+    /// The Axon account, the recipient of a transfer from CKB or the sender of
+    /// a transfer from Axon.
+    pub address:        H160,
+    /// The hash of the CKB lock script on the other side.
+    pub ckb_lock_hash:  H256,
+    /// Zero if no sUDT is transferred.
+    pub erc20_address:  H160,
+    pub sudt_type_hash: H256,
+    /// In shannons.
+    #[cfg_attr(
+        feature = "impl-serde",
+        serde(deserialize_with = "decode::deserialize_hex_u64")
+    )]
+    pub ckb_amount:     u64,
+    #[cfg_attr(
+        feature = "impl-serde",
+        serde(deserialize_with = "decode::deserialize_hex_u128")
+    )]
+    pub sudt_amount:    u128,
+}
+
+/// The transfers to be handled by the relayer, in the order of their events.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "impl-rlp",
+    derive(rlp_derive::RlpEncodable, rlp_derive::RlpDecodable)
+)]
+#[cfg_attr(feature = "impl-serde", derive(serde::Deserialize))]
+pub struct CrossChainRequests {
+    pub transfers: Vec<Transfer>,
+}
+
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
+    use alloc::vec;
+
     use super::*;
 
+    #[cfg(feature = "proof")]
     pub fn random_bytes(len: usize) -> Bytes {
         (0..len).map(|_| rand::random()).collect::<Vec<u8>>().into()
     }
 
+    #[cfg(feature = "proof")]
     #[test]
     fn test_vote_codec() {
         let vote = Vote::random();
@@ -394,4 +766,235 @@ mod tests {
         assert_eq!(vote.round, decoded.round);
         assert_eq!(vote.block_hash, decoded.block_hash);
     }
+
+    #[test]
+    fn test_transfer_codec() {
+        let requests = CrossChainRequests {
+            transfers: vec![Transfer {
+                direction: Direction::FromAxon,
+                tx_hash: H256::from([1u8; 32]),
+                ckb_amount: 100,
+                sudt_amount: u128::MAX,
+                ..Default::default()
+            }],
+        };
+        let raw = rlp::encode(&requests);
+        assert_eq!(rlp::decode::<CrossChainRequests>(&raw).unwrap(), requests);
+        assert!(matches!(
+            Direction::try_from(2),
+            Err(TypesError::InvalidDirection)
+        ));
+    }
+
+    #[test]
+    fn test_hardfork_info() {
+        let info = HardforkInfoInner {
+            block_number: 100,
+            flags:        HardforkName::Andromeda.flag(),
+        };
+        let extra = ExtraData {
+            inner: rlp::encode(&info).freeze(),
+        };
+        let decoded = extra.hardfork_info().unwrap();
+        assert_eq!(decoded, info);
+        assert!(decoded.is_enabled(HardforkName::Andromeda, 100));
+        assert!(!decoded.is_enabled(HardforkName::Andromeda, 99));
+
+        let schedule = HardforkInfo { inner: vec![info] };
+        assert_eq!(schedule.enable_height(HardforkName::Andromeda), Some(100));
+    }
+
+    #[cfg(feature = "proof")]
+    fn from_hex(s: &str) -> Vec<u8> {
+        (2..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[cfg(feature = "proof")]
+    pub(crate) fn hash(s: &str) -> H256 {
+        H256::from_slice(&from_hex(s))
+    }
+
+    #[cfg(feature = "proof")]
+    fn hex_field(value: &serde_json::Value) -> Vec<u8> {
+        from_hex(value.as_str().unwrap())
+    }
+
+    #[cfg(feature = "proof")]
+    fn hash_field(value: &serde_json::Value) -> H256 {
+        H256::from_slice(&hex_field(value))
+    }
+
+    #[cfg(feature = "proof")]
+    fn u64_field(value: &serde_json::Value) -> u64 {
+        u64::from_str_radix(&value.as_str().unwrap()[2..], 16).unwrap()
+    }
+
+    /// Read a proof in the JSON layout of `examples/proof.json`.
+    #[cfg(feature = "proof")]
+    fn proof_from_json(proof: &serde_json::Value) -> Proof {
+        Proof {
+            number:     u64_field(&proof["number"]),
+            round:      u64_field(&proof["round"]),
+            block_hash: hash_field(&proof["block_hash"]),
+            signature:  hex_field(&proof["signature"]).into(),
+            bitmap:     hex_field(&proof["bitmap"]).into(),
+        }
+    }
+
+    /// Read the block in `examples/block.json`, which the `impl-serde`
+    /// feature of this crate can not deserialize directly.
+    #[cfg(feature = "proof")]
+    pub(crate) fn fixture_block() -> AxonBlock {
+        let block: serde_json::Value =
+            serde_json::from_str(include_str!("../../examples/block.json")).unwrap();
+        let header = &block["header"];
+        assert_eq!(header["version"], "V0");
+        assert!(header["extra_data"].as_array().unwrap().is_empty());
+
+        AxonBlock {
+            header:    AxonHeader {
+                version:                  BlockVersion::V0,
+                prev_hash:                hash_field(&header["prev_hash"]),
+                proposer:                 H160::from_slice(&hex_field(&header["proposer"])),
+                state_root:               hash_field(&header["state_root"]),
+                transactions_root:        hash_field(&header["transactions_root"]),
+                signed_txs_hash:          hash_field(&header["signed_txs_hash"]),
+                receipts_root:            hash_field(&header["receipts_root"]),
+                log_bloom:                Bloom::from_slice(&hex_field(&header["log_bloom"])),
+                timestamp:                u64_field(&header["timestamp"]),
+                number:                   u64_field(&header["number"]),
+                gas_used:                 U256::from(u64_field(&header["gas_used"])),
+                gas_limit:                U256::from(u64_field(&header["gas_limit"])),
+                extra_data:               vec![],
+                base_fee_per_gas:         U256::from(u64_field(&header["base_fee_per_gas"])),
+                proof:                    proof_from_json(&header["proof"]),
+                call_system_script_count: u64_field(&header["call_system_script_count"]) as u32,
+                chain_id:                 u64_field(&header["chain_id"]),
+            },
+            tx_hashes: block["tx_hashes"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(hash_field)
+                .collect(),
+        }
+    }
+
+    /// The proof of the block in `examples/proof.json`.
+    #[cfg(feature = "proof")]
+    pub(crate) fn fixture_proof() -> Proof {
+        proof_from_json(&serde_json::from_str(include_str!("../../examples/proof.json")).unwrap())
+    }
+
+    /// The validators of the epoch in `examples/metadata.json`.
+    #[cfg(feature = "proof")]
+    pub(crate) fn fixture_validators() -> Vec<ValidatorExtend> {
+        let metadata: serde_json::Value =
+            serde_json::from_str(include_str!("../../examples/metadata.json")).unwrap();
+        metadata["verifier_list"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| ValidatorExtend {
+                bls_pub_key:    hex_field(&v["bls_pub_key"]).into(),
+                pub_key:        hex_field(&v["pub_key"]).into(),
+                address:        H160::from_slice(&hex_field(&v["address"])),
+                propose_weight: u64_field(&v["propose_weight"]) as u32,
+                vote_weight:    u64_field(&v["vote_weight"]) as u32,
+            })
+            .collect()
+    }
+
+    #[cfg(feature = "proof")]
+    #[test]
+    fn test_block_hash() {
+        let block = fixture_block();
+        let proof = fixture_proof();
+        let previous_state_root =
+            hash("0x9fc948be2cfb0127e979dc9c7e6d2f4a2890b54e0e81fd69c687303e6b25ddde");
+
+        // The validators signed the proposal hash of the block.
+        assert_eq!(proof.number, block.header.number);
+        assert_eq!(block.hash(previous_state_root), proof.block_hash);
+        assert_ne!(block.hash(H256::zero()), proof.block_hash);
+    }
+
+    /// The RLP item counts of the header and of the proposal of every block
+    /// version.
+    #[cfg(feature = "proof")]
+    const VERSION_MATRIX: &[(BlockVersion, usize, usize)] = &[(BlockVersion::V0, 17, 13)];
+
+    #[cfg(feature = "proof")]
+    fn header() -> AxonHeader {
+        AxonHeader {
+            version:                  BlockVersion::V0,
+            prev_hash:                H256::from([1u8; 32]),
+            proposer:                 H160::from([2u8; 20]),
+            state_root:               H256::from([3u8; 32]),
+            transactions_root:        H256::from([4u8; 32]),
+            signed_txs_hash:          H256::from([5u8; 32]),
+            receipts_root:            H256::from([6u8; 32]),
+            log_bloom:                Bloom::default(),
+            timestamp:                1_690_000_000,
+            number:                   100,
+            gas_used:                 U256::from(21000),
+            gas_limit:                U256::from(MAX_BLOCK_GAS_LIMIT),
+            extra_data:               vec![ExtraData::default()],
+            base_fee_per_gas:         U256::from(BASE_FEE_PER_GAS),
+            proof:                    Proof::default(),
+            call_system_script_count: 1,
+            chain_id:                 2022,
+        }
+    }
+
+    #[cfg(feature = "proof")]
+    fn with_version(raw: &[u8], version: u8) -> Vec<u8> {
+        let r = Rlp::new(raw);
+        let mut s = RlpStream::new_list(r.item_count().unwrap());
+        s.begin_list(1).append(&version);
+        for item in r.iter().skip(1) {
+            s.append_raw(item.as_raw(), 1);
+        }
+        s.out().to_vec()
+    }
+
+    #[cfg(feature = "proof")]
+    #[test]
+    fn test_block_version_matrix() {
+        let versions = VERSION_MATRIX.iter().map(|v| v.0).collect::<Vec<_>>();
+        assert_eq!(versions, BlockVersion::ALL);
+        assert_eq!(BlockVersion::ALL.last(), Some(&BlockVersion::LATEST));
+
+        for (version, header_items, proposal_items) in VERSION_MATRIX.iter().copied() {
+            let mut header = header();
+            header.version = version;
+
+            let raw = rlp::encode(&header);
+            assert_eq!(Rlp::new(&raw).item_count().unwrap(), header_items);
+            assert_eq!(rlp::decode::<AxonHeader>(&raw).unwrap(), header);
+            assert_eq!(header.hash(), H256(crate::keccak_256(&raw)));
+
+            let block = AxonBlock {
+                header,
+                tx_hashes: vec![H256::from([7u8; 32])],
+            };
+            let proposal = Proposal::from((block, H256::from([8u8; 32])));
+            let raw = rlp::encode(&proposal);
+            assert_eq!(Rlp::new(&raw).item_count().unwrap(), proposal_items);
+            let decoded = rlp::decode::<Proposal>(&raw).unwrap();
+            assert_eq!(decoded.rlp_bytes(), raw);
+            assert_eq!(decoded.hash(), proposal.hash());
+        }
+
+        let next = BlockVersion::ALL.len() as u8;
+        assert!(matches!(
+            BlockVersion::try_from(next),
+            Err(TypesError::InvalidBlockVersion(v)) if v == next
+        ));
+        let raw = with_version(&rlp::encode(&header()), next);
+        assert!(rlp::decode::<AxonHeader>(&raw).is_err());
+    }
 }
@@ -0,0 +1,252 @@
+use alloc::vec::Vec;
+
+use ethereum_types::{H160, H256, U256};
+
+use crate::consts::{EMPTY_CODE_HASH, EMPTY_TRIE_ROOT};
+use crate::proof::verify_trie_proof;
+use crate::types::{Account, AccountProof, Hex, StorageProof};
+use crate::{keccak_256, Error};
+
+/// The state of an account and of its requested storage slots, proven under a
+/// state root.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProvenAccount {
+    pub address: H160,
+    /// `None` if the account does not exist.
+    pub account: Option<Account>,
+    /// The key and the value of every requested slot, in the order of the
+    /// storage proofs. The value of an empty slot is zero.
+    pub storage: Vec<(U256, U256)>,
+}
+
+/// Verify an `eth_getProof` response against a verified state root, usually
+/// the `state_root` of a verified `AxonHeader`.
+///
+/// The account is looked up under `keccak(address)` and each storage slot
+/// under `keccak(slot)` of the account's storage root. Both can be proven
+/// absent, in which case the response must claim an empty account or a zero
+/// slot.
+pub fn verify_account_proof(
+    state_root: H256,
+    proof: &AccountProof,
+) -> Result<ProvenAccount, Error> {
+    let raw = verify_trie_proof(
+        state_root,
+        &keccak_256(proof.address.as_bytes()),
+        to_nodes(&proof.account_proof),
+    )?;
+    let account = raw.map(|raw| rlp::decode::<Account>(&raw)).transpose()?;
+
+    let storage_root = match &account {
+        Some(account) => {
+            if account.nonce != proof.nonce
+                || account.balance != proof.balance
+                || account.storage_root != proof.storage_hash
+                || account.code_hash != proof.code_hash
+            {
+                return Err(Error::AccountMismatch(proof.address));
+            }
+            Some(account.storage_root)
+        }
+        None => {
+            // Clients report either the empty hashes or zeros for a missing
+            // account.
+            if !proof.nonce.is_zero()
+                || !proof.balance.is_zero()
+                || ![H256::zero(), EMPTY_TRIE_ROOT].contains(&proof.storage_hash)
+                || ![H256::zero(), EMPTY_CODE_HASH].contains(&proof.code_hash)
+            {
+                return Err(Error::AccountMismatch(proof.address));
+            }
+            None
+        }
+    };
+
+    let storage = proof
+        .storage_proof
+        .iter()
+        .map(|slot| {
+            let value = match storage_root {
+                Some(root) => verify_storage_proof(root, slot)?,
+                None => U256::zero(),
+            };
+            if value != slot.value {
+                return Err(Error::StorageMismatch(slot.key));
+            }
+            Ok((slot.key, value))
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    Ok(ProvenAccount {
+        address: proof.address,
+        account,
+        storage,
+    })
+}
+
+/// Verify a storage slot against the storage root of its account, and return
+/// the stored value, which is zero if the slot is proven empty.
+pub fn verify_storage_proof(storage_root: H256, proof: &StorageProof) -> Result<U256, Error> {
+    if storage_root == EMPTY_TRIE_ROOT {
+        return Ok(U256::zero());
+    }
+
+    let mut slot = [0u8; 32];
+    proof.key.to_big_endian(&mut slot);
+    let raw = verify_trie_proof(storage_root, &keccak_256(&slot), to_nodes(&proof.proof))?;
+
+    Ok(match raw {
+        Some(raw) => rlp::decode(&raw)?,
+        None => U256::zero(),
+    })
+}
+
+fn to_nodes(proof: &[Hex]) -> Vec<Vec<u8>> {
+    proof.iter().map(|node| node.as_bytes().to_vec()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use cita_trie::{MemoryDB, PatriciaTrie, Trie};
+
+    use crate::hash::InnerKeccak;
+
+    use super::*;
+
+    fn slot_key(key: u64) -> Vec<u8> {
+        let mut slot = [0u8; 32];
+        U256::from(key).to_big_endian(&mut slot);
+        keccak_256(&slot).to_vec()
+    }
+
+    fn hexes(proof: Vec<Vec<u8>>) -> Vec<Hex> {
+        proof.into_iter().map(Hex::encode).collect()
+    }
+
+    #[test]
+    fn test_verify_account_proof() {
+        let hasher = Arc::new(InnerKeccak);
+        let mut storage = PatriciaTrie::new(Arc::new(MemoryDB::new(true)), Arc::clone(&hasher));
+        for (key, value) in [(0u64, 7u64), (1, 0x1234)] {
+            storage
+                .insert(slot_key(key), rlp::encode(&U256::from(value)).to_vec())
+                .unwrap();
+        }
+
+        let address = H160::from([1u8; 20]);
+        let account = Account {
+            nonce:        U256::from(3),
+            balance:      U256::exp10(18),
+            storage_root: H256::from_slice(&storage.root().unwrap()),
+            code_hash:    H256::from([2u8; 32]),
+        };
+        let mut state = PatriciaTrie::new(Arc::new(MemoryDB::new(true)), hasher);
+        state
+            .insert(
+                keccak_256(address.as_bytes()).to_vec(),
+                rlp::encode(&account).to_vec(),
+            )
+            .unwrap();
+        state
+            .insert(
+                keccak_256(&[3u8; 20]).to_vec(),
+                rlp::encode(&Account::default()).to_vec(),
+            )
+            .unwrap();
+        let state_root = H256::from_slice(&state.root().unwrap());
+
+        let mut proof = AccountProof {
+            address,
+            account_proof: hexes(state.get_proof(&keccak_256(address.as_bytes())).unwrap()),
+            balance: account.balance,
+            code_hash: account.code_hash,
+            nonce: account.nonce,
+            storage_hash: account.storage_root,
+            storage_proof: [(1u64, 0x1234u64), (2, 0)]
+                .into_iter()
+                .map(|(key, value)| StorageProof {
+                    key:   U256::from(key),
+                    value: U256::from(value),
+                    proof: hexes(storage.get_proof(&slot_key(key)).unwrap()),
+                })
+                .collect(),
+        };
+
+        let proven = verify_account_proof(state_root, &proof).unwrap();
+        assert_eq!(proven.account, Some(account.clone()));
+        assert_eq!(proven.storage, vec![
+            (U256::from(1), U256::from(0x1234)),
+            (U256::from(2), U256::zero())
+        ]);
+
+        // A proof cut down to the root node must not prove the account or the
+        // slot absent.
+        let mut truncated = proof.clone();
+        truncated.account_proof.truncate(1);
+        assert!(matches!(
+            verify_account_proof(state_root, &truncated),
+            Err(Error::VerifyMptProof)
+        ));
+        let mut truncated = proof.storage_proof[0].clone();
+        truncated.proof.truncate(1);
+        truncated.value = U256::zero();
+        assert!(matches!(
+            verify_storage_proof(account.storage_root, &truncated),
+            Err(Error::VerifyMptProof)
+        ));
+
+        proof.storage_proof[1].value = U256::one();
+        assert!(matches!(
+            verify_account_proof(state_root, &proof),
+            Err(Error::StorageMismatch(_))
+        ));
+
+        proof.storage_proof.clear();
+        proof.balance = U256::zero();
+        assert!(matches!(
+            verify_account_proof(state_root, &proof),
+            Err(Error::AccountMismatch(_))
+        ));
+
+        let missing = H160::from([4u8; 20]);
+        let mut proof = AccountProof {
+            address: missing,
+            account_proof: hexes(state.get_proof(&keccak_256(missing.as_bytes())).unwrap()),
+            code_hash: EMPTY_CODE_HASH,
+            storage_hash: EMPTY_TRIE_ROOT,
+            storage_proof: vec![StorageProof::default()],
+            ..Default::default()
+        };
+        let proven = verify_account_proof(state_root, &proof).unwrap();
+        assert!(proven.account.is_none());
+        assert_eq!(proven.storage, vec![(U256::zero(), U256::zero())]);
+
+        proof.nonce = U256::one();
+        assert!(matches!(
+            verify_account_proof(state_root, &proof),
+            Err(Error::AccountMismatch(_))
+        ));
+    }
+
+    #[test]
+    fn test_deserialize_account_proof() {
+        let json = r#"{
+            "address": "0x0101010101010101010101010101010101010101",
+            "accountProof": ["0x80"],
+            "balance": "0xde0b6b3a7640000",
+            "codeHash": "0xc5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470",
+            "nonce": "0x3",
+            "storageHash": "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421",
+            "storageProof": [{"key": "0x1", "value": "0x0", "proof": []}]
+        }"#;
+        let proof: AccountProof = serde_json::from_str(json).unwrap();
+        assert_eq!(proof.balance, U256::exp10(18));
+        assert_eq!(proof.nonce, U256::from(3));
+        assert_eq!(proof.code_hash, EMPTY_CODE_HASH);
+        assert_eq!(proof.storage_hash, EMPTY_TRIE_ROOT);
+        assert_eq!(proof.account_proof, vec![Hex::encode([0x80u8])]);
+        assert_eq!(proof.storage_proof[0].key, U256::one());
+    }
+}
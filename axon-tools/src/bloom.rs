@@ -0,0 +1,146 @@
+//! The 2048 bits logs bloom of the Ethereum yellow paper, as used by the
+//! `logs_bloom` of a receipt and the `log_bloom` of a header.
+//!
+//! A bloom can tell that a log is surely absent, but a match only means that
+//! the log may be present, so the receipts still have to be fetched and
+//! checked after a match.
+
+use ethereum_types::{Bloom, H160, H256};
+
+use crate::keccak_256;
+use crate::types::{AxonHeader, Log, Receipt};
+
+const BLOOM_BYTE_LENGTH: usize = 256;
+
+/// Set the three bits of `input` in the bloom.
+pub fn accrue(bloom: &mut Bloom, input: &[u8]) {
+    for (byte, mask) in bits(input) {
+        bloom.0[byte] |= mask;
+    }
+}
+
+/// Build the bloom of some logs from the addresses and topics of every log.
+pub fn logs_bloom<'a, I>(logs: I) -> Bloom
+where
+    I: IntoIterator<Item = &'a Log>,
+{
+    let mut bloom = Bloom::zero();
+    for log in logs {
+        accrue(&mut bloom, log.address.as_bytes());
+        for topic in log.topics.iter() {
+            accrue(&mut bloom, topic.as_bytes());
+        }
+    }
+    bloom
+}
+
+/// Whether the three bits of `input` are all set in the bloom.
+pub fn contains_input(bloom: &Bloom, input: &[u8]) -> bool {
+    bits(input).all(|(byte, mask)| bloom.0[byte] & mask == mask)
+}
+
+pub fn contains_address(bloom: &Bloom, address: &H160) -> bool {
+    contains_input(bloom, address.as_bytes())
+}
+
+pub fn contains_topic(bloom: &Bloom, topic: &H256) -> bool {
+    contains_input(bloom, topic.as_bytes())
+}
+
+/// Whether every bit of `inner` is also set in `outer`.
+pub fn contains_bloom(outer: &Bloom, inner: &Bloom) -> bool {
+    outer
+        .0
+        .iter()
+        .zip(inner.0.iter())
+        .all(|(outer, inner)| outer & inner == *inner)
+}
+
+/// Whether the bloom of a receipt matches its logs, and is contained in the
+/// bloom of the header of its block.
+pub fn header_contains_receipt(header: &AxonHeader, receipt: &Receipt) -> bool {
+    receipt.logs_bloom == logs_bloom(receipt.logs.iter())
+        && contains_bloom(&header.log_bloom, &receipt.logs_bloom)
+}
+
+/// The byte index and the bit mask of the three bits of `input`, each taken
+/// from the low 11 bits of a pair of bytes of its keccak hash.
+fn bits(input: &[u8]) -> impl Iterator<Item = (usize, u8)> {
+    let hash = keccak_256(input);
+    [0usize, 2, 4].into_iter().map(move |i| {
+        let bit = (usize::from(hash[i]) << 8 | usize::from(hash[i + 1])) & 0x7ff;
+        (BLOOM_BYTE_LENGTH - 1 - bit / 8, 1u8 << (bit % 8))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use ethereum_types::BloomInput;
+
+    use crate::types::AxonBlock;
+
+    use super::*;
+
+    fn logs() -> Vec<Log> {
+        vec![
+            Log {
+                address: H160::from([1u8; 20]),
+                topics:  vec![H256::from([2u8; 32]), H256::from([3u8; 32])],
+                data:    Bytes::new(),
+            },
+            Log {
+                address: H160::from([4u8; 20]),
+                topics:  Vec::new(),
+                data:    Bytes::from(vec![5u8; 32]),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_logs_bloom() {
+        let logs = logs();
+        let bloom = logs_bloom(logs.iter());
+
+        let mut expect = Bloom::zero();
+        for log in logs.iter() {
+            expect.accrue(BloomInput::Raw(log.address.as_bytes()));
+            for topic in log.topics.iter() {
+                expect.accrue(BloomInput::Raw(topic.as_bytes()));
+            }
+        }
+        assert_eq!(bloom, expect);
+        assert_eq!(logs_bloom(&[]), Bloom::zero());
+
+        assert!(contains_address(&bloom, &H160::from([1u8; 20])));
+        assert!(contains_address(&bloom, &H160::from([4u8; 20])));
+        assert!(contains_topic(&bloom, &H256::from([3u8; 32])));
+        assert!(!contains_address(&bloom, &H160::from([2u8; 20])));
+        assert!(!contains_topic(&bloom, &H256::from([4u8; 32])));
+    }
+
+    #[test]
+    fn test_header_contains_receipt() {
+        let logs = logs();
+        let mut receipt = Receipt {
+            tx_type: 2,
+            status: 1,
+            logs_bloom: logs_bloom(logs[..1].iter()),
+            logs: logs[..1].to_vec(),
+            ..Default::default()
+        };
+        let mut block: AxonBlock =
+            serde_json::from_str(include_str!("../../examples/block.json")).unwrap();
+        block.header.log_bloom = logs_bloom(logs.iter());
+
+        assert!(contains_bloom(&block.header.log_bloom, &receipt.logs_bloom));
+        assert!(header_contains_receipt(&block.header, &receipt));
+
+        receipt.logs_bloom = Bloom::zero();
+        assert!(!header_contains_receipt(&block.header, &receipt));
+
+        receipt.logs = vec![Log::default()];
+        receipt.logs_bloom = logs_bloom(receipt.logs.iter());
+        assert!(!header_contains_receipt(&block.header, &receipt));
+    }
+}
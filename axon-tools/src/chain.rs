@@ -0,0 +1,187 @@
+use std::fmt::{self, Display};
+
+use ethereum_types::H256;
+
+use crate::metadata::MetadataStore;
+use crate::proof::QuorumRule;
+use crate::report::VerificationReport;
+use crate::types::{AxonBlock, AxonHeader, BlockNumber};
+
+#[derive(Debug)]
+pub enum BrokenLink {
+    PrevHash {
+        expect: H256,
+        actual: H256,
+    },
+    Number {
+        expect: BlockNumber,
+        actual: BlockNumber,
+    },
+    /// No stored epoch covers the block number.
+    Metadata,
+    Proof(Box<VerificationReport>),
+}
+
+/// The first link of a header chain that does not hold.
+#[derive(Debug)]
+pub struct ChainError {
+    /// The position of the offending block in the given sequence.
+    pub index:  usize,
+    pub number: BlockNumber,
+    pub link:   BrokenLink,
+}
+
+impl Display for ChainError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.link {
+            BrokenLink::PrevHash { expect, actual } => write!(
+                f,
+                "Block {} prev hash mismatch, expect {:?}, get {:?}",
+                self.number, expect, actual
+            ),
+            BrokenLink::Number { expect, actual } => write!(
+                f,
+                "Block number mismatch, expect {}, get {}",
+                expect, actual
+            ),
+            BrokenLink::Metadata => write!(f, "Metadata of block {} not found", self.number),
+            BrokenLink::Proof(report) => write!(
+                f,
+                "Block {} proof failed: {:?}",
+                self.number, report.failure
+            ),
+        }
+    }
+}
+
+/// Verify that `blocks` extend the `trusted` header one by one.
+///
+/// Every block must point to the header hash of its predecessor and increase
+/// the block number by one. Its proposal is rebuilt with the state root of the
+/// predecessor, and is verified against the proof carried by the header of the
+/// next block, signed by the validators of the epoch the block belongs to. The
+/// last block has no successor in the sequence, so it is only linked and not
+/// verified. Returns the header of the last verified block.
+pub fn verify_header_chain(
+    trusted: &AxonHeader,
+    blocks: &[AxonBlock],
+    metadata: &MetadataStore,
+) -> Result<AxonHeader, ChainError> {
+    let mut head = trusted;
+
+    for (index, window) in blocks.windows(2).enumerate() {
+        let (block, next) = (&window[0], &window[1]);
+        check_link(index, head, block)?;
+
+        let report = metadata
+            .verify_proof_with_report(
+                block.clone(),
+                head.state_root,
+                next.header.proof.clone(),
+                QuorumRule::VoteWeight,
+            )
+            .map_err(|_| ChainError {
+                index,
+                number: block.header.number,
+                link: BrokenLink::Metadata,
+            })?;
+        if !report.is_ok() {
+            return Err(ChainError {
+                index,
+                number: block.header.number,
+                link: BrokenLink::Proof(Box::new(report)),
+            });
+        }
+
+        head = &block.header;
+    }
+
+    if let Some(last) = blocks.last() {
+        check_link(blocks.len() - 1, head, last)?;
+    }
+
+    Ok(head.clone())
+}
+
+/// Check that `block` directly follows `prev`.
+fn check_link(index: usize, prev: &AxonHeader, block: &AxonBlock) -> Result<(), ChainError> {
+    let number = block.header.number;
+    let prev_hash = prev.hash();
+
+    if block.header.prev_hash != prev_hash {
+        return Err(ChainError {
+            index,
+            number,
+            link: BrokenLink::PrevHash {
+                expect: prev_hash,
+                actual: block.header.prev_hash,
+            },
+        });
+    }
+
+    if prev.number.checked_add(1) != Some(number) {
+        return Err(ChainError {
+            index,
+            number,
+            link: BrokenLink::Number {
+                expect: prev.number.saturating_add(1),
+                actual: number,
+            },
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::{Metadata, MetadataVersion};
+
+    use super::*;
+
+    fn child_of(parent: &AxonBlock) -> AxonBlock {
+        let mut block = parent.clone();
+        block.header.prev_hash = parent.header.hash();
+        block.header.number = parent.header.number + 1;
+        block
+    }
+
+    #[test]
+    fn test_broken_links() {
+        let trusted: AxonBlock =
+            serde_json::from_str(include_str!("../../examples/block.json")).unwrap();
+        let metadata = MetadataStore::new(vec![Metadata {
+            version: MetadataVersion::new(0, u64::MAX),
+            ..Default::default()
+        }])
+        .unwrap();
+
+        let block = child_of(&trusted);
+        let head = verify_header_chain(&trusted.header, std::slice::from_ref(&block), &metadata);
+        assert_eq!(head.unwrap(), trusted.header);
+
+        let mut forked = block.clone();
+        forked.header.prev_hash = H256::zero();
+        let err = verify_header_chain(&trusted.header, &[forked], &metadata).unwrap_err();
+        assert_eq!(err.index, 0);
+        assert!(matches!(err.link, BrokenLink::PrevHash { .. }));
+
+        let mut skipped = block.clone();
+        skipped.header.number += 1;
+        let err = verify_header_chain(&trusted.header, &[skipped], &metadata).unwrap_err();
+        assert!(matches!(err.link, BrokenLink::Number { .. }));
+
+        // The copied header carries a proof of another block.
+        let err = verify_header_chain(
+            &trusted.header,
+            &[block.clone(), child_of(&block)],
+            &metadata,
+        )
+        .unwrap_err();
+        assert_eq!(err.index, 0);
+        assert!(matches!(err.link, BrokenLink::Proof(_)));
+
+        let err = verify_header_chain(&block.header, &[child_of(&trusted)], &metadata).unwrap_err();
+        assert!(matches!(err.link, BrokenLink::PrevHash { .. }));
+    }
+}
@@ -1,5 +1,7 @@
 use std::fmt::{self, Display};
 
+use crate::types::{BlockNumber, H160, U256};
+
 #[allow(dead_code)]
 #[derive(Debug)]
 pub enum Error {
@@ -7,6 +9,26 @@ pub enum Error {
     NotEnoughSignatures,
     VerifyMptProof,
     HexPrefix,
+    InvalidMetadataVersion {
+        start: BlockNumber,
+        end:   BlockNumber,
+    },
+    MetadataNotContinuous {
+        expect: BlockNumber,
+        actual: BlockNumber,
+    },
+    MetadataNotFound(BlockNumber),
+    MetadataMismatch(u64),
+    UnknownSigner,
+    DuplicateSigner,
+    TransactionNotFound(u64),
+    TransactionHashMismatch(u64),
+    AccountMismatch(H160),
+    StorageMismatch(U256),
+
+    #[cfg(feature = "impl-rlp")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "impl-rlp")))]
+    Rlp(rlp::DecoderError),
 
     #[cfg(feature = "hex")]
     #[cfg_attr(doc_cfg, doc(cfg(feature = "hex")))]
@@ -29,6 +51,14 @@ impl From<faster_hex::Error> for Error {
     }
 }
 
+#[cfg(feature = "impl-rlp")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "impl-rlp")))]
+impl From<rlp::DecoderError> for Error {
+    fn from(e: rlp::DecoderError) -> Self {
+        Self::Rlp(e)
+    }
+}
+
 #[cfg(feature = "proof")]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "proof")))]
 impl From<blst::BLST_ERROR> for Error {
@@ -52,6 +82,28 @@ impl Display for Error {
             Error::NotEnoughSignatures => write!(f, "Not enough signatures"),
             Error::VerifyMptProof => write!(f, "Verify mpt proof"),
             Error::HexPrefix => write!(f, "Hex prefix"),
+            Error::InvalidMetadataVersion { start, end } => {
+                write!(f, "Invalid metadata version [{}, {}]", start, end)
+            }
+            Error::MetadataNotContinuous { expect, actual } => write!(
+                f,
+                "Metadata not continuous, expect boundary {}, get {}",
+                expect, actual
+            ),
+            Error::MetadataNotFound(number) => write!(f, "Metadata of block {} not found", number),
+            Error::MetadataMismatch(epoch) => write!(f, "Metadata of epoch {} mismatch", epoch),
+            Error::UnknownSigner => write!(f, "Signer is not a validator"),
+            Error::DuplicateSigner => write!(f, "Signer signs more than once"),
+            Error::TransactionNotFound(index) => {
+                write!(f, "Transaction {} not found in block", index)
+            }
+            Error::TransactionHashMismatch(index) => {
+                write!(f, "Transaction {} hash mismatch", index)
+            }
+            Error::AccountMismatch(address) => write!(f, "Account {:?} mismatch", address),
+            Error::StorageMismatch(key) => write!(f, "Storage slot {:#x} mismatch", key),
+            #[cfg(feature = "impl-rlp")]
+            Error::Rlp(e) => write!(f, "Rlp error: {:?}", e),
             #[cfg(feature = "hex")]
             Error::Hex(e) => write!(f, "Hex error: {:?}", e),
             #[cfg(feature = "proof")]
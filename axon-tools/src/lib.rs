@@ -2,6 +2,13 @@
 
 extern crate alloc;
 
+#[cfg(feature = "proof")]
+mod account;
+#[cfg(feature = "hash")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "hash")))]
+pub mod bloom;
+#[cfg(feature = "proof")]
+mod chain;
 mod error;
 #[cfg(feature = "hash")]
 pub mod hash;
@@ -9,14 +16,61 @@ pub mod hash;
 #[cfg_attr(doc_cfg, doc(cfg(feature = "hex")))]
 pub mod hex;
 #[cfg(feature = "proof")]
+mod metadata;
+#[cfg(feature = "proof")]
 mod proof;
+#[cfg(feature = "proof")]
+mod prover;
+#[cfg(feature = "proof")]
+mod receipt;
+#[cfg(feature = "proof")]
+mod report;
+#[cfg(feature = "sign")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "sign")))]
+pub mod sign;
+#[cfg(feature = "hash")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "hash")))]
+pub mod system_contracts;
+#[cfg(feature = "proof")]
+mod transaction;
+#[cfg(feature = "proof")]
+mod trie;
 pub mod types;
+#[cfg(feature = "proof")]
+mod validator_set;
 
+#[cfg(feature = "proof")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "proof")))]
+pub use account::{verify_account_proof, verify_storage_proof, ProvenAccount};
+#[cfg(feature = "proof")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "proof")))]
+pub use chain::{verify_header_chain, BrokenLink, ChainError};
 pub use error::Error;
+#[cfg(feature = "proof")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "proof")))]
+pub use metadata::{verify_metadata_proof, MetadataStore};
 
 #[cfg(feature = "proof")]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "proof")))]
-pub use proof::{verify_proof, verify_trie_proof};
+pub use proof::{
+    verify_proof, verify_proof_by_proposal, verify_proof_with_quorum, verify_proof_with_report,
+    verify_trie_multiproof, verify_trie_proof, QuorumRule,
+};
+#[cfg(feature = "proof")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "proof")))]
+pub use prover::TrieProver;
+#[cfg(feature = "proof")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "proof")))]
+pub use receipt::verify_receipt_proof;
+#[cfg(feature = "proof")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "proof")))]
+pub use report::{ValidatorVote, VerificationFailure, VerificationReport, VerificationStage};
+#[cfg(feature = "proof")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "proof")))]
+pub use transaction::verify_transaction_proof;
+#[cfg(feature = "proof")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "proof")))]
+pub use validator_set::{BatchError, ValidatorSet};
 
 #[cfg(feature = "hash")]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "hash")))]
@@ -0,0 +1,370 @@
+use alloc::vec::Vec;
+
+use ethereum_types::H256;
+
+use crate::consts::METADATA_CONTRACT_ADDRESS;
+use crate::proof::{verify_trie_proof, QuorumRule};
+use crate::report::VerificationReport;
+use crate::types::{Account, AxonBlock, BlockNumber, Metadata, Proof, ValidatorExtend};
+use crate::validator_set::ValidatorSet;
+use crate::{keccak_256, Error};
+
+/// The metadata of consecutive epochs, used to pick the validator set of a
+/// block by its number.
+///
+/// The versions of the stored epochs never overlap and leave no gap between
+/// each other, so every block number is covered by at most one epoch.
+#[derive(Default, Clone, Debug, PartialEq, Eq)]
+pub struct MetadataStore {
+    epochs:         Vec<Metadata>,
+    validator_sets: Vec<ValidatorSet>,
+}
+
+impl MetadataStore {
+    pub fn new(mut epochs: Vec<Metadata>) -> Result<Self, Error> {
+        epochs.sort_by_key(|m| m.version.start);
+
+        let mut store = MetadataStore {
+            epochs:         Vec::with_capacity(epochs.len()),
+            validator_sets: Vec::with_capacity(epochs.len()),
+        };
+        for metadata in epochs {
+            store.insert(metadata)?;
+        }
+
+        Ok(store)
+    }
+
+    /// Insert an epoch right before the first or right after the last stored
+    /// one.
+    pub fn insert(&mut self, mut metadata: Metadata) -> Result<(), Error> {
+        let version = metadata.version;
+        if version.start > version.end {
+            return Err(Error::InvalidMetadataVersion {
+                start: version.start,
+                end:   version.end,
+            });
+        }
+
+        metadata.verifier_list.sort();
+        let validator_set = ValidatorSet::try_from(&metadata)?;
+
+        let (first, last) = match (self.epochs.first(), self.epochs.last()) {
+            (Some(first), Some(last)) => (first.version, last.version),
+            _ => {
+                self.epochs.push(metadata);
+                self.validator_sets.push(validator_set);
+                return Ok(());
+            }
+        };
+
+        if version.start > last.end {
+            if version.start != last.end + 1 {
+                return Err(Error::MetadataNotContinuous {
+                    expect: last.end + 1,
+                    actual: version.start,
+                });
+            }
+            self.epochs.push(metadata);
+            self.validator_sets.push(validator_set);
+        } else if version.end < first.start {
+            if version.end + 1 != first.start {
+                return Err(Error::MetadataNotContinuous {
+                    expect: first.start - 1,
+                    actual: version.end,
+                });
+            }
+            self.epochs.insert(0, metadata);
+            self.validator_sets.insert(0, validator_set);
+        } else {
+            return Err(Error::MetadataNotContinuous {
+                expect: last.end.saturating_add(1),
+                actual: version.start,
+            });
+        }
+
+        Ok(())
+    }
+
+    pub fn epochs(&self) -> &[Metadata] {
+        &self.epochs
+    }
+
+    fn position(&self, number: BlockNumber) -> Option<usize> {
+        let idx = self.epochs.partition_point(|m| m.version.end < number);
+        self.epochs
+            .get(idx)
+            .filter(|m| m.version.contains(number))
+            .map(|_| idx)
+    }
+
+    /// Get the metadata of the epoch which contains the given block.
+    pub fn get(&self, number: BlockNumber) -> Option<&Metadata> {
+        self.position(number).map(|idx| &self.epochs[idx])
+    }
+
+    /// Get the validator set of the epoch which contains the given block.
+    pub fn validator_set(&self, number: BlockNumber) -> Option<&ValidatorSet> {
+        self.position(number).map(|idx| &self.validator_sets[idx])
+    }
+
+    /// Get the sorted validators of the epoch which contains the given block.
+    pub fn verifier_list(&self, number: BlockNumber) -> Option<&[ValidatorExtend]> {
+        self.validator_set(number).map(|set| set.validators())
+    }
+
+    /// Verify the proof of a block with the validators of its epoch.
+    pub fn verify_proof(
+        &self,
+        block: AxonBlock,
+        previous_state_root: H256,
+        proof: Proof,
+    ) -> Result<(), Error> {
+        self.verify_proof_with_report(block, previous_state_root, proof, QuorumRule::VoteWeight)?
+            .into_result()
+    }
+
+    /// Verify the proof of a block with the validators of its epoch, and
+    /// report the signers, the weights and the failed check if any.
+    pub fn verify_proof_with_report(
+        &self,
+        block: AxonBlock,
+        previous_state_root: H256,
+        proof: Proof,
+        quorum: QuorumRule,
+    ) -> Result<VerificationReport, Error> {
+        let number = block.header.number;
+        let validator_set = self
+            .validator_set(number)
+            .ok_or(Error::MetadataNotFound(number))?;
+
+        Ok(validator_set.verify_proof_with_report(block, previous_state_root, proof, quorum))
+    }
+}
+
+/// Verify that the claimed metadata is stored by the metadata system contract
+/// under a verified state root, and return the stored one.
+///
+/// The metadata is read RLP encoded from the storage trie of the contract
+/// account under `storage_key`. This crate does not derive the key, so it must
+/// be the one the contract stores the claimed epoch under. The stored metadata
+/// must have the same version, epoch, verifier list and consensus config as
+/// the claimed one, the propose counter is not compared since it changes block
+/// by block.
+pub fn verify_metadata_proof(
+    state_root: H256,
+    account_proof: Vec<Vec<u8>>,
+    storage_key: &[u8],
+    storage_proof: Vec<Vec<u8>>,
+    metadata: &Metadata,
+) -> Result<Metadata, Error> {
+    let account = verify_trie_proof(
+        state_root,
+        &keccak_256(METADATA_CONTRACT_ADDRESS.as_bytes()),
+        account_proof,
+    )?
+    .ok_or(Error::VerifyMptProof)?;
+    let account: Account = rlp::decode(&account)?;
+
+    let raw = verify_trie_proof(account.storage_root, storage_key, storage_proof)?
+        .ok_or(Error::VerifyMptProof)?;
+    let stored: Metadata = rlp::decode(&raw)?;
+
+    if stored.version != metadata.version
+        || stored.epoch != metadata.epoch
+        || stored.verifier_list != metadata.verifier_list
+        || stored.consensus_config != metadata.consensus_config
+    {
+        return Err(Error::MetadataMismatch(metadata.epoch));
+    }
+
+    Ok(stored)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use cita_trie::{MemoryDB, PatriciaTrie, Trie};
+    use ethereum_types::U256;
+    use rlp::RlpStream;
+
+    use crate::hash::InnerKeccak;
+    use crate::types::{MetadataVersion, ProposeCount};
+
+    use super::*;
+
+    fn metadata(epoch: u64, start: BlockNumber, end: BlockNumber) -> Metadata {
+        Metadata {
+            version: MetadataVersion::new(start, end),
+            epoch,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_metadata_store() {
+        let store = MetadataStore::new(vec![
+            metadata(1, 100, 199),
+            metadata(0, 0, 99),
+            metadata(2, 200, 299),
+        ])
+        .unwrap();
+
+        assert_eq!(store.get(0).unwrap().epoch, 0);
+        assert_eq!(store.get(99).unwrap().epoch, 0);
+        assert_eq!(store.get(100).unwrap().epoch, 1);
+        assert_eq!(store.get(299).unwrap().epoch, 2);
+        assert!(store.get(300).is_none());
+
+        let mut inserted = MetadataStore::new(vec![metadata(1, 100, 199)]).unwrap();
+        inserted.insert(metadata(0, 0, 99)).unwrap();
+        inserted.insert(metadata(2, 200, 299)).unwrap();
+        assert_eq!(inserted, store);
+    }
+
+    #[test]
+    fn test_reject_overlap_and_gap() {
+        assert!(matches!(
+            MetadataStore::new(vec![metadata(0, 0, 99), metadata(1, 99, 199)]),
+            Err(Error::MetadataNotContinuous {
+                expect: 100,
+                actual: 99,
+            })
+        ));
+        assert!(matches!(
+            MetadataStore::new(vec![metadata(0, 0, 99), metadata(1, 101, 199)]),
+            Err(Error::MetadataNotContinuous {
+                expect: 100,
+                actual: 101,
+            })
+        ));
+        assert!(matches!(
+            MetadataStore::new(vec![metadata(0, 10, 9)]),
+            Err(Error::InvalidMetadataVersion {
+                start: 10,
+                end:   9,
+            })
+        ));
+
+        let mut store = MetadataStore::new(vec![metadata(1, 100, 199)]).unwrap();
+        assert!(store.insert(metadata(0, 0, 98)).is_err());
+        assert!(store.insert(metadata(0, 0, 99)).is_ok());
+        assert!(store.insert(metadata(2, 150, 299)).is_err());
+        assert_eq!(store.epochs().len(), 2);
+
+        // An epoch overlapping the last one, which ends at the last block.
+        let mut store = MetadataStore::new(vec![metadata(0, 0, u64::MAX)]).unwrap();
+        assert!(matches!(
+            store.insert(metadata(1, 100, u64::MAX)),
+            Err(Error::MetadataNotContinuous {
+                expect: u64::MAX,
+                actual: 100,
+            })
+        ));
+    }
+
+    #[test]
+    fn test_verify_without_epoch() {
+        let block: AxonBlock =
+            serde_json::from_str(include_str!("../../examples/block.json")).unwrap();
+        let store = MetadataStore::new(vec![metadata(0, 0, 99)]).unwrap();
+
+        assert!(matches!(
+            store.verify_proof(block, H256::zero(), Proof::default()),
+            Err(Error::MetadataNotFound(0xe7c3))
+        ));
+    }
+
+    #[test]
+    fn test_verify_metadata_proof() {
+        let metadata: Metadata =
+            serde_json::from_str(include_str!("../../examples/metadata.json")).unwrap();
+        let mut stored = metadata.clone();
+        stored.propose_counter = vec![ProposeCount {
+            address: Default::default(),
+            count:   3,
+        }];
+
+        // Any key works here, since the caller supplies it.
+        let storage_key = keccak_256(&stored.epoch.to_be_bytes());
+        let hasher = Arc::new(InnerKeccak);
+        let mut storage = PatriciaTrie::new(Arc::new(MemoryDB::new(true)), Arc::clone(&hasher));
+        storage
+            .insert(storage_key.to_vec(), rlp::encode(&stored).to_vec())
+            .unwrap();
+        let storage_root = storage.root().unwrap();
+
+        let mut account = RlpStream::new_list(4);
+        account
+            .append(&U256::zero())
+            .append(&U256::zero())
+            .append(&storage_root)
+            .append(&H256::zero());
+        let account_key = keccak_256(METADATA_CONTRACT_ADDRESS.as_bytes()).to_vec();
+        let mut state = PatriciaTrie::new(Arc::new(MemoryDB::new(true)), hasher);
+        state
+            .insert(account_key.clone(), account.out().to_vec())
+            .unwrap();
+        state.insert(vec![1u8; 32], vec![2u8; 40]).unwrap();
+        let state_root = H256::from_slice(&state.root().unwrap());
+
+        let account_proof = state.get_proof(&account_key).unwrap();
+        let storage_proof = storage.get_proof(&storage_key).unwrap();
+
+        let proven = verify_metadata_proof(
+            state_root,
+            account_proof.clone(),
+            &storage_key,
+            storage_proof.clone(),
+            &metadata,
+        )
+        .unwrap();
+        assert_eq!(proven, stored);
+
+        let mut forged = metadata.clone();
+        forged.verifier_list.pop();
+        assert!(matches!(
+            verify_metadata_proof(
+                state_root,
+                account_proof.clone(),
+                &storage_key,
+                storage_proof.clone(),
+                &forged
+            ),
+            Err(Error::MetadataMismatch(0))
+        ));
+
+        forged = metadata.clone();
+        forged.consensus_config.interval += 1;
+        assert!(matches!(
+            verify_metadata_proof(
+                state_root,
+                account_proof.clone(),
+                &storage_key,
+                storage_proof.clone(),
+                &forged
+            ),
+            Err(Error::MetadataMismatch(0))
+        ));
+
+        forged = metadata;
+        forged.epoch = 1;
+        assert!(verify_metadata_proof(
+            state_root,
+            account_proof.clone(),
+            &storage_key,
+            storage_proof,
+            &forged
+        )
+        .is_err());
+
+        // The key of another epoch is absent from the storage trie.
+        let other_key = keccak_256(&1u64.to_be_bytes());
+        let other_proof = storage.get_proof(&other_key).unwrap();
+        assert!(
+            verify_metadata_proof(state_root, account_proof, &other_key, other_proof, &stored)
+                .is_err()
+        );
+    }
+}
@@ -1,56 +1,204 @@
 use alloc::vec::Vec;
 
-use bit_vec::BitVec;
 use blst::min_pk::{AggregatePublicKey, PublicKey, Signature};
 use blst::BLST_ERROR;
 use bytes::Bytes;
 use ethereum_types::H256;
-use rlp::Encodable;
 
-use crate::types::{AxonBlock, Proof, Proposal, ValidatorExtend, Vote};
-use crate::{error::Error, hash::InnerKeccak, keccak_256};
+use crate::report::{VerificationReport, VerificationStage};
+use crate::trie::NodeSet;
+use crate::types::{AxonBlock, MultiProof, Proof, Proposal, ValidatorExtend, Vote};
+use crate::{error::Error, keccak_256};
 
-const DST: &str = "BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RONUL";
+pub(crate) const DST: &str = "BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RONUL";
 
+/// The rule used to decide whether the signers of a proof reach the quorum.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QuorumRule {
+    /// The sum of the signers' `vote_weight` must be more than 2/3 of the
+    /// total vote weight of the validator set.
+    #[default]
+    VoteWeight,
+    /// The number of signers must be more than 2/3 of the validator count,
+    /// regardless of their vote weight.
+    SignerCount,
+}
+
+/// Verify a Merkle Patricia proof of `key` under `root`, and return the value
+/// stored under it, or `None` if the proof shows that the key is absent.
+///
+/// A proof missing a node on the path of the key is rejected, rather than read
+/// as the absence of the key.
 pub fn verify_trie_proof(
     root: H256,
     key: &[u8],
     proof: Vec<Vec<u8>>,
 ) -> Result<Option<Vec<u8>>, Error> {
-    let value = cita_trie::verify_proof(&root.0, key, proof, InnerKeccak::default())?;
+    let value = NodeSet::new(root, &proof).get(key)?;
     log::debug!("key: {:?}, value: {:?}", key, value);
     Ok(value)
 }
 
+/// Verify the proofs of many keys sharing one node set, and return the value
+/// or the absence of every key in order.
+///
+/// The nodes are hashed only once, then every key is read from the same node
+/// set, with the same result as `verify_trie_proof` for each key.
+pub fn verify_trie_multiproof(
+    root: H256,
+    proof: &MultiProof,
+) -> Result<Vec<Option<Vec<u8>>>, Error> {
+    let nodes = NodeSet::new(root, &proof.nodes);
+    proof.keys.iter().map(|key| nodes.get(key)).collect()
+}
+
+/// Verify the proof of a block with the vote weight based quorum rule.
 pub fn verify_proof(
     block: AxonBlock,
     previous_state_root: H256,
     validator_list: &mut [ValidatorExtend],
     proof: Proof,
 ) -> Result<(), Error> {
-    let raw_proposal = Proposal {
-        version:                  block.header.version,
-        prev_hash:                block.header.prev_hash,
-        proposer:                 block.header.proposer,
-        prev_state_root:          previous_state_root,
-        transactions_root:        block.header.transactions_root,
-        signed_txs_hash:          block.header.signed_txs_hash,
-        timestamp:                block.header.timestamp,
-        number:                   block.header.number,
-        gas_limit:                block.header.gas_limit,
-        extra_data:               block.header.extra_data,
-        base_fee_per_gas:         block.header.base_fee_per_gas,
-        proof:                    block.header.proof,
-        chain_id:                 block.header.chain_id,
-        call_system_script_count: block.header.call_system_script_count,
-        tx_hashes:                block.tx_hashes,
+    verify_proof_with_quorum(
+        block,
+        previous_state_root,
+        validator_list,
+        proof,
+        QuorumRule::VoteWeight,
+    )
+}
+
+/// Verify the proof of a block, deciding the quorum with the given rule.
+pub fn verify_proof_with_quorum(
+    block: AxonBlock,
+    previous_state_root: H256,
+    validator_list: &mut [ValidatorExtend],
+    proof: Proof,
+    quorum: QuorumRule,
+) -> Result<(), Error> {
+    verify_proof_with_report(block, previous_state_root, validator_list, proof, quorum)
+        .into_result()
+}
+
+/// Verify the proof of a proposal with the vote weight based quorum rule.
+///
+/// The proposal already carries the previous state root and the transaction
+/// hashes, so no `AxonBlock` is needed.
+pub fn verify_proof_by_proposal(
+    proposal: &Proposal,
+    validator_list: &mut [ValidatorExtend],
+    proof: Proof,
+) -> Result<(), Error> {
+    validator_list.sort();
+
+    verify_sorted(
+        proposal.hash(),
+        validator_list,
+        None,
+        proof,
+        QuorumRule::VoteWeight,
+    )
+    .into_result()
+}
+
+/// Verify the proof of a block and report the signers, the weights and the
+/// failed check if any.
+pub fn verify_proof_with_report(
+    block: AxonBlock,
+    previous_state_root: H256,
+    validator_list: &mut [ValidatorExtend],
+    proof: Proof,
+    quorum: QuorumRule,
+) -> VerificationReport {
+    validator_list.sort();
+
+    verify_sorted(
+        Proposal::from((block, previous_state_root)).hash(),
+        validator_list,
+        None,
+        proof,
+        quorum,
+    )
+}
+
+/// The aggregated public key and signature of a proof, together with the hash
+/// of the vote they sign.
+pub(crate) struct SignedVote {
+    pub(crate) pub_key:   PublicKey,
+    pub(crate) signature: Signature,
+    pub(crate) hash_vote: [u8; 32],
+}
+
+/// Verify a proof of the given proposal hash with validators that are already
+/// sorted. The public keys of them are parsed from `validator_list` unless the
+/// validated ones are given.
+pub(crate) fn verify_sorted(
+    proposal_hash: H256,
+    validator_list: &[ValidatorExtend],
+    pub_keys: Option<&[PublicKey]>,
+    proof: Proof,
+    quorum: QuorumRule,
+) -> VerificationReport {
+    let (report, signed) = prepare_sorted(proposal_hash, validator_list, pub_keys, &proof, quorum);
+    let signed = match signed {
+        Some(signed) => signed,
+        None => return report,
+    };
+
+    let res = signed.signature.verify(
+        true,
+        &signed.hash_vote,
+        DST.as_bytes(),
+        &[],
+        &signed.pub_key,
+        pub_keys.is_none(),
+    );
+
+    if res == BLST_ERROR::BLST_SUCCESS {
+        return report;
+    }
+
+    report.fail(VerificationStage::Signature, res.into())
+}
+
+/// Run every check of `verify_sorted` except the pairing. The aggregated public
+/// key and signature are returned only if all of these checks pass, otherwise
+/// the report tells the failed one.
+pub(crate) fn prepare_sorted(
+    proposal_hash: H256,
+    validator_list: &[ValidatorExtend],
+    pub_keys: Option<&[PublicKey]>,
+    proof: &Proof,
+    quorum: QuorumRule,
+) -> (VerificationReport, Option<SignedVote>) {
+    let report = VerificationReport::new(proposal_hash, validator_list, &proof.bitmap, quorum);
+
+    if proposal_hash != proof.block_hash {
+        return (
+            report.fail(VerificationStage::BlockHash, Error::InvalidProofBlockHash),
+            None,
+        );
     }
-    .rlp_bytes();
 
-    if keccak_256(&raw_proposal) != proof.block_hash.0 {
-        return Err(Error::InvalidProofBlockHash);
+    log::debug!(
+        "signers: {}, validator len: {}, signed weight: {}, total weight: {}",
+        report.signers.len(),
+        validator_list.len(),
+        report.signed_weight,
+        report.total_weight
+    );
+    if !report.is_quorum_reached() {
+        return (
+            report.fail(VerificationStage::Quorum, Error::NotEnoughSignatures),
+            None,
+        );
     }
 
+    let pub_key = match aggregate_pks(validator_list, pub_keys, &report) {
+        Ok(pk) => pk,
+        Err(e) => return (report.fail(VerificationStage::PublicKey, e), None),
+    };
+
     let vote = Vote {
         height:     proof.number,
         round:      proof.round,
@@ -59,46 +207,150 @@ pub fn verify_proof(
     };
 
     let hash_vote = keccak_256(rlp::encode(&vote).as_ref());
-    let pks = extract_pks(&proof, validator_list)?;
-    let pks = pks.iter().collect::<Vec<_>>();
-    let c_pk = PublicKey::from_aggregate(&AggregatePublicKey::aggregate(&pks, true)?);
-    let sig = Signature::from_bytes(&proof.signature)?;
-    let res = sig.verify(true, &hash_vote, DST.as_bytes(), &[], &c_pk, true);
+    let signature = match Signature::from_bytes(&proof.signature) {
+        Ok(sig) => sig,
+        Err(e) => return (report.fail(VerificationStage::Signature, e.into()), None),
+    };
 
-    if res == BLST_ERROR::BLST_SUCCESS {
-        return Ok(());
+    (
+        report,
+        Some(SignedVote {
+            pub_key,
+            signature,
+            hash_vote,
+        }),
+    )
+}
+
+fn aggregate_pks(
+    sorted_validators: &[ValidatorExtend],
+    pub_keys: Option<&[PublicKey]>,
+    report: &VerificationReport,
+) -> Result<PublicKey, Error> {
+    let mut pks = Vec::with_capacity(report.signers.len());
+
+    for (i, vote) in report.votes.iter().enumerate() {
+        if !vote.signed {
+            continue;
+        }
+
+        let pk = match pub_keys {
+            Some(pub_keys) => pub_keys[i],
+            None => PublicKey::from_bytes(&sorted_validators[i].bls_pub_key.as_bytes())?,
+        };
+        pks.push(pk);
     }
 
-    Err(res.into())
+    // The given public keys have been validated when they were cached.
+    let pks = pks.iter().collect::<Vec<_>>();
+    Ok(PublicKey::from_aggregate(&AggregatePublicKey::aggregate(
+        &pks,
+        pub_keys.is_none(),
+    )?))
 }
 
-fn extract_pks(
-    proof: &Proof,
-    validator_list: &mut [ValidatorExtend],
-) -> Result<Vec<PublicKey>, Error> {
-    validator_list.sort();
+#[cfg(test)]
+mod tests {
+    use blst::min_pk::SecretKey;
 
-    let bit_map = BitVec::from_bytes(&proof.bitmap);
-    let mut pks = Vec::with_capacity(validator_list.len());
-    let mut count = 0usize;
+    use crate::types::{Hex, Metadata};
 
-    for (v, bit) in validator_list.iter().zip(bit_map.iter()) {
-        if !bit {
-            continue;
+    use super::*;
+
+    fn validator(seed: u8, vote_weight: u32) -> ValidatorExtend {
+        let sk = SecretKey::key_gen(&[seed; 32], &[]).unwrap();
+        ValidatorExtend {
+            bls_pub_key: Hex::encode(sk.sk_to_pk().to_bytes()),
+            pub_key: Hex::encode([seed; 33]),
+            vote_weight,
+            ..Default::default()
         }
+    }
 
-        pks.push(PublicKey::from_bytes(&v.bls_pub_key.as_bytes())?);
-        count += 1;
+    fn report(
+        validators: &mut [ValidatorExtend],
+        bitmap: u8,
+        quorum: QuorumRule,
+    ) -> VerificationReport {
+        validators.sort();
+        VerificationReport::new(H256::zero(), validators, &[bitmap], quorum)
     }
 
-    log::debug!(
-        "extract_pks count: {}, validator len: {}",
-        count,
-        validator_list.len()
-    );
-    if count * 3 <= validator_list.len() * 2 {
-        return Err(Error::NotEnoughSignatures);
+    #[test]
+    fn test_quorum_by_vote_weight() {
+        // Sorted by public key, the first validator holds most of the weight.
+        let mut validators = vec![
+            validator(1, 10),
+            validator(2, 1),
+            validator(3, 1),
+            validator(4, 1),
+        ];
+
+        assert!(report(&mut validators, 0b1000_0000, QuorumRule::VoteWeight).is_quorum_reached());
+        assert!(!report(&mut validators, 0b1000_0000, QuorumRule::SignerCount).is_quorum_reached());
+        assert!(!report(&mut validators, 0b0111_0000, QuorumRule::VoteWeight).is_quorum_reached());
+        assert!(report(&mut validators, 0b0111_0000, QuorumRule::SignerCount).is_quorum_reached());
     }
 
-    Ok(pks)
+    #[test]
+    fn test_quorum_with_max_weights() {
+        let mut validators = (1..=4).map(|i| validator(i, u32::MAX)).collect::<Vec<_>>();
+
+        assert!(report(&mut validators, 0b1110_0000, QuorumRule::VoteWeight).is_quorum_reached());
+        assert!(!report(&mut validators, 0b1100_0000, QuorumRule::VoteWeight).is_quorum_reached());
+    }
+
+    #[test]
+    fn test_report_failure_stage() {
+        let mut validators = (1..=4).map(|i| validator(i, 1)).collect::<Vec<_>>();
+        let block: AxonBlock =
+            serde_json::from_str(include_str!("../../examples/block.json")).unwrap();
+        let mut proof = block.header.proof.clone();
+        proof.bitmap = Bytes::from(vec![0b1110_0000]);
+
+        let report = verify_proof_with_report(
+            block.clone(),
+            H256::zero(),
+            &mut validators,
+            proof.clone(),
+            QuorumRule::VoteWeight,
+        );
+        assert_eq!(report.failure.unwrap().stage, VerificationStage::BlockHash);
+
+        proof.block_hash = report.proposal_hash;
+        let report = verify_proof_with_report(
+            block,
+            H256::zero(),
+            &mut validators,
+            proof,
+            QuorumRule::VoteWeight,
+        );
+        assert_eq!(report.signers.len(), 3);
+        assert_eq!(report.quorum_threshold, 3);
+        assert_eq!(report.failure.unwrap().stage, VerificationStage::Signature);
+    }
+
+    #[test]
+    fn test_verify_proof_by_proposal() {
+        let block: AxonBlock =
+            serde_json::from_str(include_str!("../../examples/block.json")).unwrap();
+        let proof: Proof = serde_json::from_str(include_str!("../../examples/proof.json")).unwrap();
+        let metadata: Metadata =
+            serde_json::from_str(include_str!("../../examples/metadata.json")).unwrap();
+        let mut validators = metadata.verifier_list;
+        let previous_state_root: H256 = serde_json::from_str(
+            "\"0x9fc948be2cfb0127e979dc9c7e6d2f4a2890b54e0e81fd69c687303e6b25ddde\"",
+        )
+        .unwrap();
+
+        let mut proposal = Proposal::from((block.clone(), previous_state_root));
+        assert!(verify_proof_by_proposal(&proposal, &mut validators, proof.clone()).is_ok());
+        assert!(verify_proof(block, previous_state_root, &mut validators, proof.clone()).is_ok());
+
+        proposal.timestamp += 1;
+        assert!(matches!(
+            verify_proof_by_proposal(&proposal, &mut validators, proof),
+            Err(Error::InvalidProofBlockHash)
+        ));
+    }
 }
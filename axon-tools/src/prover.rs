@@ -0,0 +1,179 @@
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use alloc::vec::Vec;
+
+use bytes::Bytes;
+use cita_trie::{MemoryDB, PatriciaTrie, Trie};
+use ethereum_types::H256;
+
+use crate::hash::InnerKeccak;
+use crate::types::{MultiProof, Receipt, UnverifiedTransaction};
+use crate::Error;
+
+/// An in-memory Patricia trie of the encoded receipts or transactions of a
+/// block, keyed by the RLP encoded index, the same way as `receipts_root` and
+/// `transactions_root` are built.
+///
+/// The generated proofs can be passed to `verify_trie_proof`,
+/// `verify_receipt_proof` and `verify_transaction_proof`.
+pub struct TrieProver {
+    trie: PatriciaTrie<MemoryDB, InnerKeccak>,
+    root: H256,
+    len:  usize,
+}
+
+impl TrieProver {
+    /// Build the trie of the encoded items, in the order of their indices.
+    pub fn new<I, T>(items: I) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<[u8]>,
+    {
+        let mut trie = PatriciaTrie::new(Arc::new(MemoryDB::new(true)), Arc::new(InnerKeccak));
+        let mut len = 0usize;
+        for (index, item) in items.into_iter().enumerate() {
+            trie.insert(
+                rlp::encode(&(index as u64)).to_vec(),
+                item.as_ref().to_vec(),
+            )?;
+            len += 1;
+        }
+        let root = H256::from_slice(&trie.root()?);
+
+        Ok(TrieProver { trie, root, len })
+    }
+
+    pub fn from_receipts(receipts: &[Receipt]) -> Result<Self, Error> {
+        Self::new(receipts.iter().map(Receipt::encode))
+    }
+
+    pub fn from_transactions(transactions: &[UnverifiedTransaction]) -> Result<Self, Error> {
+        Self::new(transactions.iter().map(UnverifiedTransaction::encode))
+    }
+
+    pub fn root(&self) -> H256 {
+        self.root
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Generate the proof of the item at `index`. The proof of an index out
+    /// of range proves that no item is stored there.
+    pub fn generate_proof(&self, index: u64) -> Result<Vec<Vec<u8>>, Error> {
+        Ok(self.trie.get_proof(&rlp::encode(&index))?)
+    }
+
+    /// Generate one proof of the items at all `indices`, with the nodes shared
+    /// by their paths included only once.
+    pub fn generate_multiproof(&self, indices: &[u64]) -> Result<MultiProof, Error> {
+        let proofs = indices
+            .iter()
+            .map(|index| Ok((rlp::encode(index).to_vec(), self.generate_proof(*index)?)))
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok(MultiProof::merge(proofs))
+    }
+}
+
+impl MultiProof {
+    /// Merge the single proofs of some keys into a multiproof. The nodes keep
+    /// the order in which they first appear.
+    pub fn merge<I, K>(proofs: I) -> Self
+    where
+        I: IntoIterator<Item = (K, Vec<Vec<u8>>)>,
+        K: AsRef<[u8]>,
+    {
+        let mut seen = BTreeSet::new();
+        let mut multiproof = MultiProof::default();
+        for (key, proof) in proofs {
+            multiproof.keys.push(Bytes::copy_from_slice(key.as_ref()));
+            for node in proof {
+                if seen.insert(node.clone()) {
+                    multiproof.nodes.push(node.into());
+                }
+            }
+        }
+
+        multiproof
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::consts::EMPTY_TRIE_ROOT;
+    use crate::{verify_trie_multiproof, verify_trie_proof};
+
+    use super::*;
+
+    #[test]
+    fn test_trie_prover() {
+        let items = (0..200u8)
+            .map(|i| vec![i; i as usize + 1])
+            .collect::<Vec<_>>();
+        let prover = TrieProver::new(&items).unwrap();
+        assert_eq!(prover.len(), 200);
+
+        for index in [0u64, 1, 127, 128, 199] {
+            let proof = prover.generate_proof(index).unwrap();
+            let value = verify_trie_proof(prover.root(), &rlp::encode(&index), proof).unwrap();
+            assert_eq!(value.as_ref(), Some(&items[index as usize]));
+        }
+
+        let proof = prover.generate_proof(200).unwrap();
+        assert!(
+            verify_trie_proof(prover.root(), &rlp::encode(&200u64), proof)
+                .unwrap()
+                .is_none()
+        );
+
+        let empty = TrieProver::new(Vec::<Vec<u8>>::new()).unwrap();
+        assert!(empty.is_empty());
+        assert_eq!(empty.root(), EMPTY_TRIE_ROOT);
+    }
+
+    #[test]
+    fn test_multiproof() {
+        let items = (0..200u8)
+            .map(|i| vec![i; i as usize + 1])
+            .collect::<Vec<_>>();
+        let prover = TrieProver::new(&items).unwrap();
+
+        let indices = [0u64, 1, 127, 128, 199, 200];
+        let multiproof = prover.generate_multiproof(&indices).unwrap();
+        let single_len = indices
+            .iter()
+            .map(|i| prover.generate_proof(*i).unwrap().len())
+            .sum::<usize>();
+        assert!(multiproof.nodes.len() < single_len);
+
+        let encoded = rlp::encode(&multiproof);
+        assert_eq!(rlp::decode::<MultiProof>(&encoded).unwrap(), multiproof);
+
+        let values = verify_trie_multiproof(prover.root(), &multiproof).unwrap();
+        assert_eq!(values.len(), indices.len());
+        for (index, value) in indices.iter().zip(values) {
+            assert_eq!(value.as_ref(), items.get(*index as usize));
+        }
+
+        assert!(verify_trie_multiproof(H256::zero(), &multiproof).is_err());
+
+        // Without the nodes below the root, present keys must not read as
+        // absent.
+        let mut truncated = multiproof.clone();
+        truncated
+            .nodes
+            .retain(|node| crate::keccak_256(node) == prover.root().0);
+        assert_eq!(truncated.nodes.len(), 1);
+        assert!(matches!(
+            verify_trie_multiproof(prover.root(), &truncated),
+            Err(crate::Error::VerifyMptProof)
+        ));
+    }
+}
@@ -0,0 +1,96 @@
+use alloc::vec::Vec;
+
+use crate::proof::verify_trie_proof;
+use crate::types::{AxonHeader, Receipt};
+use crate::Error;
+
+/// Verify that the receipt of the `index`-th transaction of a block is stored
+/// under `header.receipts_root`, and return the decoded receipt.
+///
+/// The receipts trie is keyed by the RLP encoded transaction index. The logs of
+/// the receipt are only as trustworthy as the header, which should have been
+/// verified with its proof beforehand.
+pub fn verify_receipt_proof(
+    header: &AxonHeader,
+    index: u64,
+    proof: Vec<Vec<u8>>,
+) -> Result<Receipt, Error> {
+    let raw = verify_trie_proof(header.receipts_root, &rlp::encode(&index), proof)?
+        .ok_or(Error::VerifyMptProof)?;
+
+    Ok(Receipt::decode(&raw)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use ethereum_types::{Bloom, H160, H256, U256};
+
+    use crate::prover::TrieProver;
+    use crate::types::{AxonBlock, Log};
+
+    use super::*;
+
+    fn receipt(tx_type: u8, cumulative_gas_used: u64, logs: Vec<Log>) -> Receipt {
+        Receipt {
+            tx_type,
+            status: 1,
+            cumulative_gas_used: U256::from(cumulative_gas_used),
+            logs_bloom: Bloom::from_low_u64_be(cumulative_gas_used),
+            logs,
+        }
+    }
+
+    #[test]
+    fn test_receipt_codec() {
+        let legacy = receipt(0, 21000, Vec::new());
+        let raw = legacy.encode();
+        assert!(raw[0] >= 0xc0);
+        assert_eq!(Receipt::decode(&raw).unwrap(), legacy);
+
+        // Same shape as a receipt encoded by the Axon node: a 0x02 type byte
+        // followed by a 286 bytes long list ending with one empty log.
+        let typed = receipt(2, 10, vec![Log::default()]);
+        let raw = typed.encode();
+        assert_eq!(raw.len(), 290);
+        assert_eq!(&raw[..6], &[2u8, 249, 1, 30, 1, 10]);
+        assert_eq!(&raw[raw.len() - 3..], &[0u8, 192, 128]);
+        assert_eq!(Receipt::decode(&raw).unwrap(), typed);
+
+        assert!(Receipt::decode(&[]).is_err());
+        let typed_legacy = [&[0u8][..], &legacy.encode()].concat();
+        assert!(Receipt::decode(&typed_legacy).is_err());
+        assert!(Receipt::decode(&raw[..raw.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_verify_receipt_proof() {
+        let receipts = [
+            receipt(0, 21000, Vec::new()),
+            receipt(1, 42000, Vec::new()),
+            receipt(2, 63000, vec![Log {
+                address: H160::from([1u8; 20]),
+                topics:  vec![H256::from([2u8; 32])],
+                data:    Bytes::from(vec![3u8; 64]),
+            }]),
+        ];
+
+        let prover = TrieProver::from_receipts(&receipts).unwrap();
+        let mut block: AxonBlock =
+            serde_json::from_str(include_str!("../../examples/block.json")).unwrap();
+        block.header.receipts_root = prover.root();
+
+        let proof = prover.generate_proof(2).unwrap();
+        let proven = verify_receipt_proof(&block.header, 2, proof.clone()).unwrap();
+        assert_eq!(proven, receipts[2]);
+        assert!(proven.is_success());
+
+        assert!(matches!(
+            verify_receipt_proof(&block.header, 1, proof.clone()),
+            Err(Error::VerifyMptProof)
+        ));
+
+        block.header.receipts_root = H256::zero();
+        assert!(verify_receipt_proof(&block.header, 2, proof).is_err());
+    }
+}
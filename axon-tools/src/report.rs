@@ -0,0 +1,124 @@
+use alloc::vec::Vec;
+
+use bit_vec::BitVec;
+use ethereum_types::{H160, H256};
+
+use crate::types::ValidatorExtend;
+use crate::{error::Error, proof::QuorumRule};
+
+/// The check of a proof verification that failed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VerificationStage {
+    /// The recomputed proposal hash does not match `Proof::block_hash`.
+    BlockHash,
+    /// The signers do not reach the quorum.
+    Quorum,
+    /// A signer's BLS public key can not be decoded or aggregated.
+    PublicKey,
+    /// The aggregated signature can not be decoded or does not match.
+    Signature,
+}
+
+#[derive(Debug)]
+pub struct VerificationFailure {
+    pub stage: VerificationStage,
+    pub error: Error,
+}
+
+/// A validator and its bit in the proof bitmap.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValidatorVote {
+    pub address:     H160,
+    pub vote_weight: u32,
+    pub signed:      bool,
+}
+
+/// The outcome of a proof verification, filled as far as the verification
+/// went before it succeeded or failed.
+#[derive(Debug)]
+pub struct VerificationReport {
+    pub proposal_hash:    H256,
+    pub quorum:           QuorumRule,
+    /// Every validator in sorted order, together with its bit in the bitmap.
+    pub votes:            Vec<ValidatorVote>,
+    pub signers:          Vec<H160>,
+    pub signed_weight:    u64,
+    pub total_weight:     u64,
+    /// The minimum the signers have to reach, counted in vote weight for
+    /// `QuorumRule::VoteWeight` and in signers for `QuorumRule::SignerCount`.
+    pub quorum_threshold: u64,
+    pub failure:          Option<VerificationFailure>,
+}
+
+impl VerificationReport {
+    pub(crate) fn new(
+        proposal_hash: H256,
+        sorted_validators: &[ValidatorExtend],
+        bitmap: &[u8],
+        quorum: QuorumRule,
+    ) -> Self {
+        let bit_map = BitVec::from_bytes(bitmap);
+        let votes = sorted_validators
+            .iter()
+            .enumerate()
+            .map(|(i, v)| ValidatorVote {
+                address:     v.address,
+                vote_weight: v.vote_weight,
+                signed:      bit_map.get(i).unwrap_or(false),
+            })
+            .collect::<Vec<_>>();
+        let signers = votes
+            .iter()
+            .filter(|v| v.signed)
+            .map(|v| v.address)
+            .collect::<Vec<_>>();
+        let signed_weight = votes
+            .iter()
+            .filter(|v| v.signed)
+            .map(|v| u64::from(v.vote_weight))
+            .sum();
+        let total_weight = votes.iter().map(|v| u64::from(v.vote_weight)).sum();
+        let total = match quorum {
+            QuorumRule::VoteWeight => total_weight,
+            QuorumRule::SignerCount => votes.len() as u64,
+        };
+
+        VerificationReport {
+            proposal_hash,
+            quorum,
+            votes,
+            signers,
+            signed_weight,
+            total_weight,
+            // The smallest amount that is more than 2/3 of the total, computed
+            // in u128 so that it can not overflow.
+            quorum_threshold: (u128::from(total) * 2 / 3 + 1) as u64,
+            failure: None,
+        }
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.failure.is_none()
+    }
+
+    pub fn is_quorum_reached(&self) -> bool {
+        let signed = match self.quorum {
+            QuorumRule::VoteWeight => self.signed_weight,
+            QuorumRule::SignerCount => self.signers.len() as u64,
+        };
+
+        signed >= self.quorum_threshold
+    }
+
+    pub fn into_result(self) -> Result<(), Error> {
+        match self.failure {
+            Some(failure) => Err(failure.error),
+            None => Ok(()),
+        }
+    }
+
+    pub(crate) fn fail(mut self, stage: VerificationStage, error: Error) -> Self {
+        self.failure = Some(VerificationFailure { stage, error });
+        self
+    }
+}
@@ -0,0 +1,148 @@
+use alloc::vec::Vec;
+
+use bit_vec::BitVec;
+use blst::min_pk::{AggregateSignature, SecretKey, Signature};
+use bytes::Bytes;
+use ethereum_types::H256;
+
+use crate::proof::DST;
+use crate::types::{Proof, ValidatorExtend, Vote};
+use crate::{error::Error, keccak_256};
+
+/// Sign the precommit vote of a block the same way as Axon validators do.
+pub fn sign_precommit(
+    secret_key: &SecretKey,
+    number: u64,
+    round: u64,
+    block_hash: H256,
+) -> Signature {
+    let vote = Vote {
+        height: number,
+        round,
+        vote_type: 2u8,
+        block_hash: Bytes::from(block_hash.0.to_vec()),
+    };
+    let hash_vote = keccak_256(rlp::encode(&vote).as_ref());
+
+    secret_key.sign(&hash_vote, DST.as_bytes(), &[])
+}
+
+/// Build the proof of a block signed by the given secret keys.
+///
+/// Every signer must be in `validator_list` and appear only once. The list is
+/// sorted in place so that the bitmap follows the order `verify_proof` reads
+/// it in.
+pub fn build_proof(
+    validator_list: &mut [ValidatorExtend],
+    signers: &[SecretKey],
+    number: u64,
+    round: u64,
+    block_hash: H256,
+) -> Result<Proof, Error> {
+    validator_list.sort();
+
+    let mut bitmap = BitVec::from_elem(validator_list.len(), false);
+    let mut signatures = Vec::with_capacity(signers.len());
+
+    for sk in signers.iter() {
+        let pk = sk.sk_to_pk().to_bytes();
+        let idx = validator_list
+            .iter()
+            .position(|v| v.bls_pub_key.as_ref() == pk.as_slice())
+            .ok_or(Error::UnknownSigner)?;
+
+        if bitmap[idx] {
+            return Err(Error::DuplicateSigner);
+        }
+
+        bitmap.set(idx, true);
+        signatures.push(sign_precommit(sk, number, round, block_hash));
+    }
+
+    let signatures = signatures.iter().collect::<Vec<_>>();
+    let signature = AggregateSignature::aggregate(&signatures, true)?.to_signature();
+
+    Ok(Proof {
+        number,
+        round,
+        block_hash,
+        signature: Bytes::from(signature.to_bytes().to_vec()),
+        bitmap: Bytes::from(bitmap.to_bytes()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::{AxonBlock, Hex};
+    use crate::verify_proof;
+
+    use super::*;
+
+    fn validators(n: u8) -> (Vec<SecretKey>, Vec<ValidatorExtend>) {
+        (1..=n)
+            .map(|i| {
+                let sk = SecretKey::key_gen(&[i; 32], &[]).unwrap();
+                let validator = ValidatorExtend {
+                    bls_pub_key: Hex::encode(sk.sk_to_pk().to_bytes()),
+                    pub_key: Hex::encode([i; 33]),
+                    vote_weight: 1,
+                    ..Default::default()
+                };
+                (sk, validator)
+            })
+            .unzip()
+    }
+
+    #[test]
+    fn test_build_proof() {
+        let block: AxonBlock =
+            serde_json::from_str(include_str!("../../examples/block.json")).unwrap();
+        let previous_state_root = H256::from([1u8; 32]);
+        let hash = block.hash(previous_state_root);
+        let (sks, mut validator_list) = validators(4);
+
+        let proof =
+            build_proof(&mut validator_list, &sks[1..], block.header.number, 0, hash).unwrap();
+        assert!(verify_proof(
+            block.clone(),
+            previous_state_root,
+            &mut validator_list,
+            proof
+        )
+        .is_ok());
+
+        let proof =
+            build_proof(&mut validator_list, &sks[2..], block.header.number, 0, hash).unwrap();
+        assert!(matches!(
+            verify_proof(
+                block.clone(),
+                previous_state_root,
+                &mut validator_list,
+                proof
+            ),
+            Err(Error::NotEnoughSignatures)
+        ));
+
+        let mut proof =
+            build_proof(&mut validator_list, &sks, block.header.number, 0, hash).unwrap();
+        proof.round = 1;
+        assert!(matches!(
+            verify_proof(block, previous_state_root, &mut validator_list, proof),
+            Err(Error::Bls(_))
+        ));
+
+        let stranger = SecretKey::key_gen(&[9u8; 32], &[]).unwrap();
+        assert!(matches!(
+            build_proof(&mut validator_list, &[stranger], 0, 0, hash),
+            Err(Error::UnknownSigner)
+        ));
+
+        // The bitmap holds one bit per validator, so a repeated signer would
+        // add a signature that `verify_proof` never accounts for.
+        let signers = [sks[0].clone(), sks[1].clone(), sks[1].clone()];
+        assert!(matches!(
+            build_proof(&mut validator_list, &signers, 0, 0, hash),
+            Err(Error::DuplicateSigner)
+        ));
+    }
+}
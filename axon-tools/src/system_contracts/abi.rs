@@ -0,0 +1,497 @@
+//! A minimal Solidity ABI codec, covering the types used by the system
+//! contracts.
+//!
+//! Values are encoded into and decoded from [`Token`]s, and decoding is driven
+//! by the expected [`ParamType`]s, so the layout of a call or of a return value
+//! is written down once as a list of types.
+
+use alloc::vec::Vec;
+
+use derive_more::Display;
+use ethereum_types::{H160, H256, U256};
+
+use crate::keccak_256;
+
+const WORD: usize = 32;
+
+#[derive(Clone, Debug, PartialEq, Eq, Display)]
+pub enum AbiError {
+    #[display(fmt = "Read {} bytes at offset {} out of bounds", len, offset)]
+    OutOfBounds { offset: usize, len: usize },
+
+    #[display(fmt = "Invalid offset or length {}", _0)]
+    InvalidOffset(U256),
+
+    #[display(fmt = "Invalid value at offset {}", _0)]
+    InvalidValue(usize),
+
+    #[display(fmt = "Unexpected token")]
+    UnexpectedToken,
+
+    #[display(fmt = "Selector mismatch")]
+    SelectorMismatch,
+
+    #[display(fmt = "Decoded data exceeds the input")]
+    TooLarge,
+}
+
+impl std::error::Error for AbiError {}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParamType {
+    Address,
+    Bool,
+    Uint(usize),
+    FixedBytes(usize),
+    Bytes,
+    Array(Box<ParamType>),
+    Tuple(Vec<ParamType>),
+}
+
+impl ParamType {
+    pub fn is_dynamic(&self) -> bool {
+        match self {
+            ParamType::Bytes | ParamType::Array(_) => true,
+            ParamType::Tuple(types) => types.iter().any(ParamType::is_dynamic),
+            _ => false,
+        }
+    }
+
+    /// The size of the value in the head of its enclosing tuple.
+    fn head_len(&self) -> usize {
+        match self {
+            ParamType::Tuple(types) if !self.is_dynamic() => {
+                types.iter().map(ParamType::head_len).sum()
+            }
+            _ => WORD,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Token {
+    Address(H160),
+    Bool(bool),
+    Uint(U256),
+    FixedBytes(Vec<u8>),
+    Bytes(Vec<u8>),
+    Array(Vec<Token>),
+    Tuple(Vec<Token>),
+}
+
+impl Token {
+    pub fn is_dynamic(&self) -> bool {
+        match self {
+            Token::Bytes(_) | Token::Array(_) => true,
+            Token::Tuple(tokens) => tokens.iter().any(Token::is_dynamic),
+            _ => false,
+        }
+    }
+
+    pub fn into_address(self) -> Result<H160, AbiError> {
+        match self {
+            Token::Address(address) => Ok(address),
+            _ => Err(AbiError::UnexpectedToken),
+        }
+    }
+
+    pub fn into_bool(self) -> Result<bool, AbiError> {
+        match self {
+            Token::Bool(b) => Ok(b),
+            _ => Err(AbiError::UnexpectedToken),
+        }
+    }
+
+    pub fn into_uint(self) -> Result<U256, AbiError> {
+        match self {
+            Token::Uint(value) => Ok(value),
+            _ => Err(AbiError::UnexpectedToken),
+        }
+    }
+
+    pub fn into_u8(self) -> Result<u8, AbiError> {
+        u8::try_from(self.into_u64()?).map_err(|_| AbiError::UnexpectedToken)
+    }
+
+    pub fn into_u32(self) -> Result<u32, AbiError> {
+        u32::try_from(self.into_u64()?).map_err(|_| AbiError::UnexpectedToken)
+    }
+
+    pub fn into_u64(self) -> Result<u64, AbiError> {
+        let value = self.into_uint()?;
+        if value.bits() > 64 {
+            return Err(AbiError::UnexpectedToken);
+        }
+        Ok(value.low_u64())
+    }
+
+    pub fn into_u128(self) -> Result<u128, AbiError> {
+        let value = self.into_uint()?;
+        if value.bits() > 128 {
+            return Err(AbiError::UnexpectedToken);
+        }
+        Ok(value.low_u128())
+    }
+
+    pub fn into_fixed_bytes(self) -> Result<Vec<u8>, AbiError> {
+        match self {
+            Token::FixedBytes(bytes) => Ok(bytes),
+            _ => Err(AbiError::UnexpectedToken),
+        }
+    }
+
+    /// The value of a `bytes32`.
+    pub fn into_h256(self) -> Result<H256, AbiError> {
+        match self {
+            Token::FixedBytes(bytes) if bytes.len() == 32 => Ok(H256::from_slice(&bytes)),
+            _ => Err(AbiError::UnexpectedToken),
+        }
+    }
+
+    pub fn into_bytes(self) -> Result<Vec<u8>, AbiError> {
+        match self {
+            Token::Bytes(bytes) => Ok(bytes),
+            _ => Err(AbiError::UnexpectedToken),
+        }
+    }
+
+    pub fn into_array(self) -> Result<Vec<Token>, AbiError> {
+        match self {
+            Token::Array(tokens) => Ok(tokens),
+            _ => Err(AbiError::UnexpectedToken),
+        }
+    }
+
+    pub fn into_tuple(self) -> Result<Vec<Token>, AbiError> {
+        match self {
+            Token::Tuple(tokens) => Ok(tokens),
+            _ => Err(AbiError::UnexpectedToken),
+        }
+    }
+
+    /// The members of a tuple, to be read in order.
+    pub fn into_fields(self) -> Result<Fields, AbiError> {
+        Ok(Fields(self.into_tuple()?.into_iter()))
+    }
+}
+
+/// The members of a decoded tuple, see [`Token::into_fields`].
+pub struct Fields(alloc::vec::IntoIter<Token>);
+
+impl Fields {
+    pub fn next_field(&mut self) -> Result<Token, AbiError> {
+        self.0.next().ok_or(AbiError::UnexpectedToken)
+    }
+}
+
+/// The first 4 bytes of the keccak hash of a function signature such as
+/// `transfer(address,uint256)`.
+pub fn selector(signature: &str) -> [u8; 4] {
+    let hash = keccak_256(signature.as_bytes());
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// Encode the tokens as the members of a tuple, which is the layout of the
+/// arguments of a call and of the return values.
+pub fn encode(tokens: &[Token]) -> Vec<u8> {
+    let head_len = tokens
+        .iter()
+        .map(|t| if t.is_dynamic() { WORD } else { static_len(t) })
+        .sum::<usize>();
+    let mut head = Vec::with_capacity(head_len);
+    let mut tail = Vec::new();
+
+    for token in tokens {
+        if token.is_dynamic() {
+            head.extend_from_slice(&uint_word(U256::from(head_len + tail.len())));
+            encode_token(token, &mut tail);
+        } else {
+            encode_token(token, &mut head);
+        }
+    }
+
+    head.extend(tail);
+    head
+}
+
+pub fn encode_call(signature: &str, tokens: &[Token]) -> Vec<u8> {
+    let mut data = selector(signature).to_vec();
+    data.extend(encode(tokens));
+    data
+}
+
+/// Decode the members of a tuple.
+///
+/// Offsets may point anywhere in the data, so the elements of an array can
+/// share one tail. The bytes read while decoding are bounded by the length of
+/// the data, which every canonical encoding satisfies, to keep the decoded
+/// tokens linear in the input.
+pub fn decode(types: &[ParamType], data: &[u8]) -> Result<Vec<Token>, AbiError> {
+    Decoder {
+        data,
+        budget: data.len(),
+    }
+    .decode_tuple(types, 0)
+}
+
+/// The signature among `signatures` whose selector starts the calldata.
+pub fn find_signature<'a>(signatures: &[&'a str], calldata: &[u8]) -> Result<&'a str, AbiError> {
+    signatures
+        .iter()
+        .copied()
+        .find(|s| calldata.starts_with(&selector(s)))
+        .ok_or(AbiError::SelectorMismatch)
+}
+
+/// Check the selector of the calldata and decode the arguments.
+pub fn decode_call(
+    signature: &str,
+    types: &[ParamType],
+    data: &[u8],
+) -> Result<Vec<Token>, AbiError> {
+    if data.len() < 4 || data[..4] != selector(signature) {
+        return Err(AbiError::SelectorMismatch);
+    }
+    decode(types, &data[4..])
+}
+
+fn static_len(token: &Token) -> usize {
+    match token {
+        Token::Tuple(tokens) => tokens.iter().map(static_len).sum(),
+        _ => WORD,
+    }
+}
+
+fn uint_word(value: U256) -> [u8; WORD] {
+    let mut word = [0u8; WORD];
+    value.to_big_endian(&mut word);
+    word
+}
+
+fn encode_padded(bytes: &[u8], out: &mut Vec<u8>) {
+    out.extend_from_slice(bytes);
+    let rem = bytes.len() % WORD;
+    if rem != 0 {
+        out.resize(out.len() + WORD - rem, 0);
+    }
+}
+
+fn encode_token(token: &Token, out: &mut Vec<u8>) {
+    match token {
+        Token::Address(address) => {
+            out.extend_from_slice(&[0u8; 12]);
+            out.extend_from_slice(address.as_bytes());
+        }
+        Token::Bool(b) => out.extend_from_slice(&uint_word(U256::from(*b as u8))),
+        Token::Uint(value) => out.extend_from_slice(&uint_word(*value)),
+        Token::FixedBytes(bytes) => {
+            let mut word = [0u8; WORD];
+            word[..bytes.len()].copy_from_slice(bytes);
+            out.extend_from_slice(&word);
+        }
+        Token::Bytes(bytes) => {
+            out.extend_from_slice(&uint_word(U256::from(bytes.len())));
+            encode_padded(bytes, out);
+        }
+        Token::Array(tokens) => {
+            out.extend_from_slice(&uint_word(U256::from(tokens.len())));
+            out.extend(encode(tokens));
+        }
+        Token::Tuple(tokens) => out.extend(encode(tokens)),
+    }
+}
+
+fn read(data: &[u8], offset: usize, len: usize) -> Result<&[u8], AbiError> {
+    offset
+        .checked_add(len)
+        .and_then(|end| data.get(offset..end))
+        .ok_or(AbiError::OutOfBounds { offset, len })
+}
+
+struct Decoder<'a> {
+    data:   &'a [u8],
+    /// The bytes left to read.
+    budget: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn read(&mut self, offset: usize, len: usize) -> Result<&'a [u8], AbiError> {
+        let bytes = read(self.data, offset, len)?;
+        self.budget = self.budget.checked_sub(len).ok_or(AbiError::TooLarge)?;
+        Ok(bytes)
+    }
+
+    fn read_word(&mut self, offset: usize) -> Result<&'a [u8], AbiError> {
+        self.read(offset, WORD)
+    }
+
+    /// Read an offset or a length, which must fit in the data anyway.
+    fn read_usize(&mut self, offset: usize) -> Result<usize, AbiError> {
+        let value = U256::from_big_endian(self.read_word(offset)?);
+        if value > U256::from(self.data.len()) {
+            return Err(AbiError::InvalidOffset(value));
+        }
+        Ok(value.as_usize())
+    }
+
+    fn decode_tuple(&mut self, types: &[ParamType], base: usize) -> Result<Vec<Token>, AbiError> {
+        let mut tokens = Vec::with_capacity(types.len());
+        let mut offset = base;
+
+        for ty in types {
+            let token = if ty.is_dynamic() {
+                let tail = base
+                    .checked_add(self.read_usize(offset)?)
+                    .ok_or(AbiError::OutOfBounds { offset, len: WORD })?;
+                self.decode_param(ty, tail)?
+            } else {
+                self.decode_param(ty, offset)?
+            };
+            tokens.push(token);
+            offset += ty.head_len();
+        }
+
+        Ok(tokens)
+    }
+
+    fn decode_param(&mut self, ty: &ParamType, offset: usize) -> Result<Token, AbiError> {
+        match ty {
+            ParamType::Address => {
+                let word = self.read_word(offset)?;
+                if word[..12].iter().any(|b| *b != 0) {
+                    return Err(AbiError::InvalidValue(offset));
+                }
+                Ok(Token::Address(H160::from_slice(&word[12..])))
+            }
+            ParamType::Bool => {
+                let value = U256::from_big_endian(self.read_word(offset)?);
+                if value > U256::one() {
+                    return Err(AbiError::InvalidValue(offset));
+                }
+                Ok(Token::Bool(!value.is_zero()))
+            }
+            ParamType::Uint(bits) => {
+                let value = U256::from_big_endian(self.read_word(offset)?);
+                if value.bits() > *bits {
+                    return Err(AbiError::InvalidValue(offset));
+                }
+                Ok(Token::Uint(value))
+            }
+            ParamType::FixedBytes(len) => {
+                let word = self.read_word(offset)?;
+                Ok(Token::FixedBytes(word[..*len].to_vec()))
+            }
+            ParamType::Bytes => {
+                let len = self.read_usize(offset)?;
+                Ok(Token::Bytes(self.read(offset + WORD, len)?.to_vec()))
+            }
+            ParamType::Array(inner) => {
+                let len = self.read_usize(offset)?;
+                // Every element takes at least a word, which bounds the
+                // allocation by the length of the data.
+                read(self.data, offset + WORD, len * inner.head_len())?;
+                let types = vec![inner.as_ref().clone(); len];
+                self.decode_tuple(&types, offset + WORD).map(Token::Array)
+            }
+            ParamType::Tuple(types) => self.decode_tuple(types, offset).map(Token::Tuple),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selector() {
+        assert_eq!(selector("transfer(address,uint256)"), [
+            0xa9, 0x05, 0x9c, 0xbb
+        ]);
+        assert_eq!(selector("balanceOf(address)"), [0x70, 0xa0, 0x82, 0x31]);
+    }
+
+    #[test]
+    fn test_codec() {
+        // The example of the Solidity ABI specification, for
+        // `f(uint256,uint32[],bytes10,bytes)` called with
+        // `(0x123, [0x456, 0x789], "1234567890", "Hello, world!")`.
+        let expect = crate::hex::hex_decode(concat!(
+            "0000000000000000000000000000000000000000000000000000000000000123",
+            "0000000000000000000000000000000000000000000000000000000000000080",
+            "3132333435363738393000000000000000000000000000000000000000000000",
+            "00000000000000000000000000000000000000000000000000000000000000e0",
+            "0000000000000000000000000000000000000000000000000000000000000002",
+            "0000000000000000000000000000000000000000000000000000000000000456",
+            "0000000000000000000000000000000000000000000000000000000000000789",
+            "000000000000000000000000000000000000000000000000000000000000000d",
+            "48656c6c6f2c20776f726c642100000000000000000000000000000000000000",
+        ))
+        .unwrap();
+        let types = [
+            ParamType::Uint(256),
+            ParamType::Array(Box::new(ParamType::Uint(32))),
+            ParamType::FixedBytes(10),
+            ParamType::Bytes,
+        ];
+        let tokens = vec![
+            Token::Uint(U256::from(0x123)),
+            Token::Array(vec![
+                Token::Uint(U256::from(0x456)),
+                Token::Uint(U256::from(0x789)),
+            ]),
+            Token::FixedBytes(b"1234567890".to_vec()),
+            Token::Bytes(b"Hello, world!".to_vec()),
+        ];
+
+        assert_eq!(encode(&tokens), expect);
+        assert_eq!(decode(&types, &expect).unwrap(), tokens);
+
+        let call = encode_call("f(uint256,uint32[],bytes10,bytes)", &tokens);
+        assert_eq!(call[4..], expect);
+        assert_eq!(
+            decode_call("f(uint256,uint32[],bytes10,bytes)", &types, &call).unwrap(),
+            tokens
+        );
+        assert_eq!(
+            decode_call("g(uint256,uint32[],bytes10,bytes)", &types, &call),
+            Err(AbiError::SelectorMismatch)
+        );
+
+        // 0x789 does not fit in an uint8.
+        let narrow = [
+            ParamType::Uint(256),
+            ParamType::Array(Box::new(ParamType::Uint(8))),
+        ];
+        assert!(matches!(
+            decode(&narrow, &expect),
+            Err(AbiError::InvalidValue(_))
+        ));
+        assert!(matches!(
+            decode(&types, &expect[..expect.len() - 32]),
+            Err(AbiError::OutOfBounds { .. })
+        ));
+
+        let mut huge = expect.clone();
+        huge[4 * 32 + 31] = 0xff;
+        assert!(decode(&types, &huge).is_err());
+    }
+
+    #[test]
+    fn test_decode_aliased_tails() {
+        // `bytes[]` of 64 elements, whose offsets all point to the same tail of
+        // 1024 bytes.
+        let mut data = uint_word(U256::from(WORD)).to_vec();
+        data.extend_from_slice(&uint_word(U256::from(64)));
+        for _ in 0..64 {
+            data.extend_from_slice(&uint_word(U256::from(64 * WORD)));
+        }
+        data.extend_from_slice(&uint_word(U256::from(1024)));
+        data.resize(data.len() + 1024, 0xab);
+
+        let types = [ParamType::Array(Box::new(ParamType::Bytes))];
+        assert_eq!(decode(&types, &data), Err(AbiError::TooLarge));
+
+        let tokens = vec![Token::Array(vec![Token::Bytes(vec![0xab; 1024]); 2])];
+        assert_eq!(decode(&types, &encode(&tokens)).unwrap(), tokens);
+    }
+}
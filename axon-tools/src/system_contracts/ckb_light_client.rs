@@ -0,0 +1,253 @@
+//! The calls to the CKB light client system contract at
+//! [`CKB_LIGHT_CLIENT_CONTRACT_ADDRESS`](crate::consts::CKB_LIGHT_CLIENT_CONTRACT_ADDRESS),
+//! which stores the CKB headers accepted by Axon.
+//!
+//! The contract is declared as
+//!
+//! ```solidity
+//! struct Header {
+//!     uint32 version; uint32 compactTarget; uint64 timestamp; uint64 number; uint64 epoch;
+//!     bytes32 parentHash; bytes32 transactionsRoot; bytes32 proposalsHash;
+//!     bytes32 extraHash; bytes32 dao; uint128 nonce; bytes extension; bytes32 blockHash;
+//! }
+//!
+//! contract CkbLightClient {
+//!     function update(Header[] calldata headers) external;
+//!     function rollback(bytes32[] calldata blockHashes) external;
+//!     function setState(bool allowRead) external;
+//!     function getHeader(bytes32 blockHash) external view returns (Header memory);
+//! }
+//! ```
+//!
+//! Proving an accepted header from a verified `state_root` is out of scope for
+//! now: it needs the storage slots of the contract, which have not been taken
+//! from its source, so only the calls and the return value are covered.
+
+use alloc::vec::Vec;
+
+use bytes::Bytes;
+use ethereum_types::H256;
+
+use super::abi::{self, AbiError, ParamType, Token};
+use crate::types::CkbHeader;
+
+pub const UPDATE: &str =
+    "update((uint32,uint32,uint64,uint64,uint64,bytes32,bytes32,bytes32,bytes32,bytes32,uint128,bytes,bytes32)[])";
+pub const ROLLBACK: &str = "rollback(bytes32[])";
+pub const SET_STATE: &str = "setState(bool)";
+pub const GET_HEADER: &str = "getHeader(bytes32)";
+
+/// A call to the CKB light client contract.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CkbLightClientCall {
+    /// Append headers, sent by the validators.
+    Update(Vec<CkbHeader>),
+    /// Remove the headers of the given block hashes, sent by the validators.
+    Rollback(Vec<H256>),
+    /// Whether the headers can be read by other contracts.
+    SetState(bool),
+    /// Returns the [`CkbHeader`] of a block hash.
+    GetHeader(H256),
+}
+
+impl CkbLightClientCall {
+    pub fn signature(&self) -> &'static str {
+        match self {
+            CkbLightClientCall::Update(_) => UPDATE,
+            CkbLightClientCall::Rollback(_) => ROLLBACK,
+            CkbLightClientCall::SetState(_) => SET_STATE,
+            CkbLightClientCall::GetHeader(_) => GET_HEADER,
+        }
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let token = match self {
+            CkbLightClientCall::Update(headers) => {
+                Token::Array(headers.iter().map(header_token).collect())
+            }
+            CkbLightClientCall::Rollback(hashes) => Token::Array(
+                hashes
+                    .iter()
+                    .map(|h| Token::FixedBytes(h.as_bytes().to_vec()))
+                    .collect(),
+            ),
+            CkbLightClientCall::SetState(allow_read) => Token::Bool(*allow_read),
+            CkbLightClientCall::GetHeader(hash) => Token::FixedBytes(hash.as_bytes().to_vec()),
+        };
+        abi::encode_call(self.signature(), &[token])
+    }
+
+    pub fn decode(calldata: &[u8]) -> Result<Self, AbiError> {
+        let signature = abi::find_signature(&[UPDATE, ROLLBACK, SET_STATE, GET_HEADER], calldata)?;
+        let ty = match signature {
+            UPDATE => ParamType::Array(Box::new(header_type())),
+            ROLLBACK => ParamType::Array(Box::new(ParamType::FixedBytes(32))),
+            SET_STATE => ParamType::Bool,
+            _ => ParamType::FixedBytes(32),
+        };
+        let token = abi::decode_call(signature, &[ty], calldata)?.remove(0);
+
+        Ok(match signature {
+            UPDATE => CkbLightClientCall::Update(
+                token
+                    .into_array()?
+                    .into_iter()
+                    .map(header_from_token)
+                    .collect::<Result<_, _>>()?,
+            ),
+            ROLLBACK => CkbLightClientCall::Rollback(
+                token
+                    .into_array()?
+                    .into_iter()
+                    .map(Token::into_h256)
+                    .collect::<Result<_, _>>()?,
+            ),
+            SET_STATE => CkbLightClientCall::SetState(token.into_bool()?),
+            _ => CkbLightClientCall::GetHeader(token.into_h256()?),
+        })
+    }
+}
+
+/// Decode the return value of [`CkbLightClientCall::GetHeader`].
+pub fn decode_header(output: &[u8]) -> Result<CkbHeader, AbiError> {
+    header_from_token(abi::decode(&[header_type()], output)?.remove(0))
+}
+
+/// Encode a header as the return value of [`CkbLightClientCall::GetHeader`].
+pub fn encode_header(header: &CkbHeader) -> Vec<u8> {
+    abi::encode(&[header_token(header)])
+}
+
+fn header_type() -> ParamType {
+    ParamType::Tuple(vec![
+        ParamType::Uint(32),
+        ParamType::Uint(32),
+        ParamType::Uint(64),
+        ParamType::Uint(64),
+        ParamType::Uint(64),
+        ParamType::FixedBytes(32),
+        ParamType::FixedBytes(32),
+        ParamType::FixedBytes(32),
+        ParamType::FixedBytes(32),
+        ParamType::FixedBytes(32),
+        ParamType::Uint(128),
+        ParamType::Bytes,
+        ParamType::FixedBytes(32),
+    ])
+}
+
+fn header_token(header: &CkbHeader) -> Token {
+    let hash = |h: &H256| Token::FixedBytes(h.as_bytes().to_vec());
+    Token::Tuple(vec![
+        Token::Uint(header.version.into()),
+        Token::Uint(header.compact_target.into()),
+        Token::Uint(header.timestamp.into()),
+        Token::Uint(header.number.into()),
+        Token::Uint(header.epoch.into()),
+        hash(&header.parent_hash),
+        hash(&header.transactions_root),
+        hash(&header.proposals_hash),
+        hash(&header.extra_hash),
+        hash(&header.dao),
+        Token::Uint(header.nonce.into()),
+        Token::Bytes(header.extension.to_vec()),
+        hash(&header.block_hash),
+    ])
+}
+
+fn header_from_token(token: Token) -> Result<CkbHeader, AbiError> {
+    let mut fields = token.into_fields()?;
+
+    Ok(CkbHeader {
+        version:           fields.next_field()?.into_u32()?,
+        compact_target:    fields.next_field()?.into_u32()?,
+        timestamp:         fields.next_field()?.into_u64()?,
+        number:            fields.next_field()?.into_u64()?,
+        epoch:             fields.next_field()?.into_u64()?,
+        parent_hash:       fields.next_field()?.into_h256()?,
+        transactions_root: fields.next_field()?.into_h256()?,
+        proposals_hash:    fields.next_field()?.into_h256()?,
+        extra_hash:        fields.next_field()?.into_h256()?,
+        dao:               fields.next_field()?.into_h256()?,
+        nonce:             fields.next_field()?.into_u128()?,
+        extension:         Bytes::from(fields.next_field()?.into_bytes()?),
+        block_hash:        fields.next_field()?.into_h256()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header() -> CkbHeader {
+        CkbHeader {
+            version:           0,
+            compact_target:    0x1a08a97e,
+            timestamp:         1_690_000_000_000,
+            number:            100,
+            epoch:             1 << 56 | 2 << 40 | 3,
+            parent_hash:       H256::from([1u8; 32]),
+            transactions_root: H256::from([2u8; 32]),
+            proposals_hash:    H256::from([3u8; 32]),
+            extra_hash:        H256::from([4u8; 32]),
+            dao:               H256::from([5u8; 32]),
+            nonce:             0x0102030405060708090a0b0c0d0e0f10,
+            extension:         Bytes::from(vec![0xabu8; 40]),
+            block_hash:        H256::from([6u8; 32]),
+        }
+    }
+
+    #[test]
+    fn test_decode_header() {
+        // Encoded by hand following the Solidity ABI specification.
+        let output = crate::hex::hex_decode(concat!(
+            "0000000000000000000000000000000000000000000000000000000000000020",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "000000000000000000000000000000000000000000000000000000001a08a97e",
+            "000000000000000000000000000000000000000000000000000001897bd98400",
+            "0000000000000000000000000000000000000000000000000000000000000064",
+            "0000000000000000000000000000000000000000000000000100020000000003",
+            "0101010101010101010101010101010101010101010101010101010101010101",
+            "0202020202020202020202020202020202020202020202020202020202020202",
+            "0303030303030303030303030303030303030303030303030303030303030303",
+            "0404040404040404040404040404040404040404040404040404040404040404",
+            "0505050505050505050505050505050505050505050505050505050505050505",
+            "000000000000000000000000000000000102030405060708090a0b0c0d0e0f10",
+            "00000000000000000000000000000000000000000000000000000000000001a0",
+            "0606060606060606060606060606060606060606060606060606060606060606",
+            "0000000000000000000000000000000000000000000000000000000000000028",
+            "abababababababababababababababababababababababababababababababab",
+            "abababababababab000000000000000000000000000000000000000000000000",
+        ))
+        .unwrap();
+
+        assert_eq!(decode_header(&output).unwrap(), header());
+        assert_eq!(encode_header(&header()), output);
+        assert!(decode_header(&output[..output.len() - 32]).is_err());
+    }
+
+    #[test]
+    fn test_light_client_call() {
+        let mut short = header();
+        short.extension = Bytes::new();
+        let calls = [
+            CkbLightClientCall::Update(vec![header(), short]),
+            CkbLightClientCall::Rollback(vec![H256::from([1u8; 32]), H256::from([2u8; 32])]),
+            CkbLightClientCall::SetState(true),
+            CkbLightClientCall::GetHeader(H256::from([6u8; 32])),
+        ];
+        for call in calls {
+            let calldata = call.encode();
+            assert_eq!(calldata[..4], abi::selector(call.signature()));
+            assert_eq!(CkbLightClientCall::decode(&calldata).unwrap(), call);
+        }
+
+        let mut calldata = CkbLightClientCall::SetState(true).encode();
+        calldata[35] = 2;
+        assert!(CkbLightClientCall::decode(&calldata).is_err());
+        assert_eq!(
+            CkbLightClientCall::decode(&[0u8; 36]),
+            Err(AbiError::SelectorMismatch)
+        );
+    }
+}
@@ -0,0 +1,349 @@
+//! The calls to the image cell system contract at
+//! [`IMAGE_CELL_CONTRACT_ADDRESS`](crate::consts::IMAGE_CELL_CONTRACT_ADDRESS),
+//! which mirrors the live CKB cells into Axon.
+//!
+//! The contract is declared as
+//!
+//! ```solidity
+//! struct OutPoint { bytes32 txHash; uint32 index; }
+//! struct Script { bytes32 codeHash; uint8 hashType; bytes args; }
+//! struct CellOutput { uint64 capacity; Script lock; Script[] type_; }
+//! struct CellInfo { OutPoint outPoint; CellOutput output; bytes data; }
+//! struct BlockUpdate { uint64 blockNumber; OutPoint[] txInputs; CellInfo[] txOutputs; }
+//! struct BlockRollback { OutPoint[] txInputs; OutPoint[] txOutputs; }
+//!
+//! contract ImageCell {
+//!     function update(BlockUpdate[] calldata blocks) external;
+//!     function rollback(BlockRollback[] calldata blocks) external;
+//!     function setState(bool allowRead) external;
+//! }
+//! ```
+//!
+//! where the `type_` of a cell output holds at most one script.
+//!
+//! The cell query getter and the update events are not covered yet, since
+//! their signatures have not been taken from the contract and no recorded
+//! output is available to test them against.
+
+use alloc::vec::Vec;
+
+use bytes::Bytes;
+
+use super::abi::{self, AbiError, ParamType, Token};
+use crate::types::{BlockRollback, BlockUpdate, CellInfo, CellOutput, OutPoint, Script};
+
+pub const UPDATE: &str = "update((uint64,(bytes32,uint32)[],((bytes32,uint32),(uint64,(bytes32,uint8,bytes),(bytes32,uint8,bytes)[]),bytes)[])[])";
+pub const ROLLBACK: &str = "rollback(((bytes32,uint32)[],(bytes32,uint32)[])[])";
+pub const SET_STATE: &str = "setState(bool)";
+
+/// A call to the image cell contract.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ImageCellCall {
+    /// Apply the cell changes of CKB blocks, sent by the validators.
+    Update(Vec<BlockUpdate>),
+    /// Revert the cell changes of CKB blocks, sent by the validators.
+    Rollback(Vec<BlockRollback>),
+    /// Whether the cells can be read by other contracts.
+    SetState(bool),
+}
+
+impl ImageCellCall {
+    pub fn signature(&self) -> &'static str {
+        match self {
+            ImageCellCall::Update(_) => UPDATE,
+            ImageCellCall::Rollback(_) => ROLLBACK,
+            ImageCellCall::SetState(_) => SET_STATE,
+        }
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let token = match self {
+            ImageCellCall::Update(blocks) => {
+                Token::Array(blocks.iter().map(block_update_token).collect())
+            }
+            ImageCellCall::Rollback(blocks) => Token::Array(
+                blocks
+                    .iter()
+                    .map(|b| {
+                        Token::Tuple(vec![
+                            out_points_token(&b.tx_inputs),
+                            out_points_token(&b.tx_outputs),
+                        ])
+                    })
+                    .collect(),
+            ),
+            ImageCellCall::SetState(allow_read) => Token::Bool(*allow_read),
+        };
+        abi::encode_call(self.signature(), &[token])
+    }
+
+    pub fn decode(calldata: &[u8]) -> Result<Self, AbiError> {
+        let signature = abi::find_signature(&[UPDATE, ROLLBACK, SET_STATE], calldata)?;
+        let ty = match signature {
+            UPDATE => ParamType::Array(Box::new(block_update_type())),
+            ROLLBACK => ParamType::Array(Box::new(ParamType::Tuple(vec![
+                out_points_type(),
+                out_points_type(),
+            ]))),
+            _ => ParamType::Bool,
+        };
+        let token = abi::decode_call(signature, &[ty], calldata)?.remove(0);
+
+        Ok(match signature {
+            UPDATE => ImageCellCall::Update(
+                token
+                    .into_array()?
+                    .into_iter()
+                    .map(block_update_from_token)
+                    .collect::<Result<_, _>>()?,
+            ),
+            ROLLBACK => ImageCellCall::Rollback(
+                token
+                    .into_array()?
+                    .into_iter()
+                    .map(|t| {
+                        let (tx_inputs, tx_outputs) = out_point_pair(t)?;
+                        Ok(BlockRollback {
+                            tx_inputs,
+                            tx_outputs,
+                        })
+                    })
+                    .collect::<Result<_, AbiError>>()?,
+            ),
+            _ => ImageCellCall::SetState(token.into_bool()?),
+        })
+    }
+}
+
+fn out_point_type() -> ParamType {
+    ParamType::Tuple(vec![ParamType::FixedBytes(32), ParamType::Uint(32)])
+}
+
+fn out_points_type() -> ParamType {
+    ParamType::Array(Box::new(out_point_type()))
+}
+
+fn script_type() -> ParamType {
+    ParamType::Tuple(vec![
+        ParamType::FixedBytes(32),
+        ParamType::Uint(8),
+        ParamType::Bytes,
+    ])
+}
+
+fn cell_info_type() -> ParamType {
+    ParamType::Tuple(vec![
+        out_point_type(),
+        ParamType::Tuple(vec![
+            ParamType::Uint(64),
+            script_type(),
+            ParamType::Array(Box::new(script_type())),
+        ]),
+        ParamType::Bytes,
+    ])
+}
+
+fn block_update_type() -> ParamType {
+    ParamType::Tuple(vec![
+        ParamType::Uint(64),
+        out_points_type(),
+        ParamType::Array(Box::new(cell_info_type())),
+    ])
+}
+
+fn out_point_token(out_point: &OutPoint) -> Token {
+    Token::Tuple(vec![
+        Token::FixedBytes(out_point.tx_hash.as_bytes().to_vec()),
+        Token::Uint(out_point.index.into()),
+    ])
+}
+
+fn out_points_token(out_points: &[OutPoint]) -> Token {
+    Token::Array(out_points.iter().map(out_point_token).collect())
+}
+
+fn script_token(script: &Script) -> Token {
+    Token::Tuple(vec![
+        Token::FixedBytes(script.code_hash.as_bytes().to_vec()),
+        Token::Uint(script.hash_type.into()),
+        Token::Bytes(script.args.to_vec()),
+    ])
+}
+
+fn cell_info_token(cell: &CellInfo) -> Token {
+    Token::Tuple(vec![
+        out_point_token(&cell.out_point),
+        Token::Tuple(vec![
+            Token::Uint(cell.output.capacity.into()),
+            script_token(&cell.output.lock),
+            Token::Array(cell.output.type_.iter().map(script_token).collect()),
+        ]),
+        Token::Bytes(cell.data.to_vec()),
+    ])
+}
+
+fn block_update_token(block: &BlockUpdate) -> Token {
+    Token::Tuple(vec![
+        Token::Uint(block.block_number.into()),
+        out_points_token(&block.tx_inputs),
+        Token::Array(block.tx_outputs.iter().map(cell_info_token).collect()),
+    ])
+}
+
+fn out_point_from_token(token: Token) -> Result<OutPoint, AbiError> {
+    let mut fields = token.into_fields()?;
+
+    Ok(OutPoint {
+        tx_hash: fields.next_field()?.into_h256()?,
+        index:   fields.next_field()?.into_u32()?,
+    })
+}
+
+fn out_points_from_token(token: Token) -> Result<Vec<OutPoint>, AbiError> {
+    token
+        .into_array()?
+        .into_iter()
+        .map(out_point_from_token)
+        .collect()
+}
+
+fn out_point_pair(token: Token) -> Result<(Vec<OutPoint>, Vec<OutPoint>), AbiError> {
+    let mut fields = token.into_fields()?;
+
+    Ok((
+        out_points_from_token(fields.next_field()?)?,
+        out_points_from_token(fields.next_field()?)?,
+    ))
+}
+
+fn script_from_token(token: Token) -> Result<Script, AbiError> {
+    let mut fields = token.into_fields()?;
+
+    Ok(Script {
+        code_hash: fields.next_field()?.into_h256()?,
+        hash_type: fields.next_field()?.into_u8()?,
+        args:      Bytes::from(fields.next_field()?.into_bytes()?),
+    })
+}
+
+fn cell_info_from_token(token: Token) -> Result<CellInfo, AbiError> {
+    let mut fields = token.into_fields()?;
+
+    let out_point = out_point_from_token(fields.next_field()?)?;
+    let mut output = fields.next_field()?.into_fields()?;
+    let capacity = output.next_field()?.into_u64()?;
+    let lock = script_from_token(output.next_field()?)?;
+    let mut types = output.next_field()?.into_array()?;
+    if types.len() > 1 {
+        return Err(AbiError::UnexpectedToken);
+    }
+
+    Ok(CellInfo {
+        out_point,
+        output: CellOutput {
+            capacity,
+            lock,
+            type_: types.pop().map(script_from_token).transpose()?,
+        },
+        data: Bytes::from(fields.next_field()?.into_bytes()?),
+    })
+}
+
+fn block_update_from_token(token: Token) -> Result<BlockUpdate, AbiError> {
+    let mut fields = token.into_fields()?;
+
+    Ok(BlockUpdate {
+        block_number: fields.next_field()?.into_u64()?,
+        tx_inputs:    out_points_from_token(fields.next_field()?)?,
+        tx_outputs:   fields
+            .next_field()?
+            .into_array()?
+            .into_iter()
+            .map(cell_info_from_token)
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use ethereum_types::H256;
+
+    use super::*;
+
+    fn cell() -> CellInfo {
+        CellInfo {
+            out_point: OutPoint {
+                tx_hash: H256::from([1u8; 32]),
+                index:   2,
+            },
+            output:    CellOutput {
+                capacity: 6_100_000_000,
+                lock:     Script {
+                    code_hash: H256::from([2u8; 32]),
+                    hash_type: 1,
+                    args:      Bytes::from(vec![3u8; 20]),
+                },
+                type_:    Some(Script {
+                    code_hash: H256::from([4u8; 32]),
+                    hash_type: 0,
+                    args:      Bytes::new(),
+                }),
+            },
+            data:      Bytes::from(vec![0xaau8; 4]),
+        }
+    }
+
+    #[test]
+    fn test_cell_codec() {
+        let mut no_type = cell();
+        no_type.output.type_ = None;
+        for cell in [cell(), no_type, CellInfo::default()] {
+            assert_eq!(cell_info_from_token(cell_info_token(&cell)).unwrap(), cell);
+        }
+
+        // A cell output with two type scripts.
+        let mut token = cell_info_token(&cell());
+        if let Token::Tuple(fields) = &mut token {
+            if let Token::Tuple(output) = &mut fields[1] {
+                let script = script_token(&cell().output.lock);
+                output[2] = Token::Array(vec![script.clone(), script]);
+            }
+        }
+        assert_eq!(cell_info_from_token(token), Err(AbiError::UnexpectedToken));
+    }
+
+    #[test]
+    fn test_image_cell_call() {
+        let calls = [
+            ImageCellCall::Update(vec![
+                BlockUpdate {
+                    block_number: 100,
+                    tx_inputs:    vec![OutPoint {
+                        tx_hash: H256::from([5u8; 32]),
+                        index:   0,
+                    }],
+                    tx_outputs:   vec![cell(), CellInfo::default()],
+                },
+                BlockUpdate::default(),
+            ]),
+            ImageCellCall::Rollback(vec![BlockRollback {
+                tx_inputs:  vec![cell().out_point],
+                tx_outputs: Vec::new(),
+            }]),
+            ImageCellCall::SetState(false),
+        ];
+        for call in calls {
+            let calldata = call.encode();
+            assert_eq!(calldata[..4], abi::selector(call.signature()));
+            assert_eq!(ImageCellCall::decode(&calldata).unwrap(), call);
+        }
+
+        let mut calldata = ImageCellCall::SetState(true).encode();
+        calldata[35] = 2;
+        assert!(ImageCellCall::decode(&calldata).is_err());
+        assert_eq!(
+            ImageCellCall::decode(&[0u8; 36]),
+            Err(AbiError::SelectorMismatch)
+        );
+    }
+}
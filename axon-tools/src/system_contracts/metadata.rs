@@ -0,0 +1,317 @@
+//! The calls to the `getMetadata` getter of the metadata system contract at
+//! [`METADATA_CONTRACT_ADDRESS`](crate::consts::METADATA_CONTRACT_ADDRESS),
+//! and the decoding of its return value.
+//!
+//! The Solidity layout of the returned structs follows the field order of
+//! [`Metadata`]:
+//!
+//! ```solidity
+//! struct MetadataVersion { uint64 start; uint64 end; }
+//! struct ValidatorExtend {
+//!     bytes bls_pub_key; bytes pub_key; address address_;
+//!     uint32 propose_weight; uint32 vote_weight;
+//! }
+//! struct ProposeCount { address address_; uint64 count; }
+//! struct ConsensusConfig {
+//!     uint64 gas_limit; uint64 interval; uint64 propose_ratio; uint64 prevote_ratio;
+//!     uint64 precommit_ratio; uint64 brake_ratio; uint64 tx_num_limit; uint64 max_tx_size;
+//! }
+//! struct Metadata {
+//!     MetadataVersion version; uint64 epoch; ValidatorExtend[] verifier_list;
+//!     ProposeCount[] propose_counter; ConsensusConfig consensus_config;
+//! }
+//! ```
+//!
+//! The getter of the metadata by block number is not provided, since its
+//! signature has not been taken from the contract. The layout above is not
+//! yet tested against the output of an `eth_call` to an Axon node either.
+
+use alloc::vec::Vec;
+
+use ethereum_types::U256;
+
+use super::abi::{self, AbiError, ParamType, Token};
+use crate::types::{
+    ConsensusConfig, Hex, Metadata, MetadataVersion, ProposeCount, ValidatorExtend,
+};
+
+pub const GET_METADATA: &str = "getMetadata(uint64)";
+
+/// A call to a getter of the metadata contract.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MetadataCall {
+    /// Returns the [`Metadata`] of an epoch.
+    GetMetadata(u64),
+}
+
+impl MetadataCall {
+    const ALL: [&'static str; 1] = [GET_METADATA];
+
+    pub fn signature(&self) -> &'static str {
+        match self {
+            MetadataCall::GetMetadata(_) => GET_METADATA,
+        }
+    }
+
+    fn argument(&self) -> u64 {
+        match self {
+            MetadataCall::GetMetadata(n) => *n,
+        }
+    }
+
+    /// The calldata of an `eth_call` to the metadata contract.
+    pub fn encode(&self) -> Vec<u8> {
+        abi::encode_call(self.signature(), &[Token::Uint(U256::from(
+            self.argument(),
+        ))])
+    }
+
+    pub fn decode(calldata: &[u8]) -> Result<Self, AbiError> {
+        let signature = abi::find_signature(&MetadataCall::ALL, calldata)?;
+        let n = abi::decode_call(signature, &[ParamType::Uint(64)], calldata)?
+            .remove(0)
+            .into_u64()?;
+
+        Ok(MetadataCall::GetMetadata(n))
+    }
+}
+
+/// Decode the return value of [`MetadataCall::GetMetadata`].
+pub fn decode_metadata(output: &[u8]) -> Result<Metadata, AbiError> {
+    metadata_from_token(abi::decode(&[metadata_type()], output)?.remove(0))
+}
+
+/// Encode a metadata as the return value of [`MetadataCall::GetMetadata`].
+pub fn encode_metadata(metadata: &Metadata) -> Vec<u8> {
+    abi::encode(&[metadata_token(metadata)])
+}
+
+fn validator_type() -> ParamType {
+    ParamType::Tuple(vec![
+        ParamType::Bytes,
+        ParamType::Bytes,
+        ParamType::Address,
+        ParamType::Uint(32),
+        ParamType::Uint(32),
+    ])
+}
+
+fn propose_count_type() -> ParamType {
+    ParamType::Tuple(vec![ParamType::Address, ParamType::Uint(64)])
+}
+
+fn consensus_config_type() -> ParamType {
+    ParamType::Tuple(vec![ParamType::Uint(64); 8])
+}
+
+fn metadata_type() -> ParamType {
+    ParamType::Tuple(vec![
+        ParamType::Tuple(vec![ParamType::Uint(64), ParamType::Uint(64)]),
+        ParamType::Uint(64),
+        ParamType::Array(Box::new(validator_type())),
+        ParamType::Array(Box::new(propose_count_type())),
+        consensus_config_type(),
+    ])
+}
+
+fn uint(n: u64) -> Token {
+    Token::Uint(U256::from(n))
+}
+
+fn metadata_token(metadata: &Metadata) -> Token {
+    Token::Tuple(vec![
+        Token::Tuple(vec![
+            uint(metadata.version.start),
+            uint(metadata.version.end),
+        ]),
+        uint(metadata.epoch),
+        Token::Array(metadata.verifier_list.iter().map(validator_token).collect()),
+        Token::Array(
+            metadata
+                .propose_counter
+                .iter()
+                .map(|c| Token::Tuple(vec![Token::Address(c.address), uint(c.count)]))
+                .collect(),
+        ),
+        Token::Tuple(
+            [
+                metadata.consensus_config.gas_limit,
+                metadata.consensus_config.interval,
+                metadata.consensus_config.propose_ratio,
+                metadata.consensus_config.prevote_ratio,
+                metadata.consensus_config.precommit_ratio,
+                metadata.consensus_config.brake_ratio,
+                metadata.consensus_config.tx_num_limit,
+                metadata.consensus_config.max_tx_size,
+            ]
+            .into_iter()
+            .map(uint)
+            .collect(),
+        ),
+    ])
+}
+
+fn validator_token(validator: &ValidatorExtend) -> Token {
+    Token::Tuple(vec![
+        Token::Bytes(validator.bls_pub_key.as_ref().to_vec()),
+        Token::Bytes(validator.pub_key.as_ref().to_vec()),
+        Token::Address(validator.address),
+        uint(validator.propose_weight.into()),
+        uint(validator.vote_weight.into()),
+    ])
+}
+
+fn metadata_from_token(token: Token) -> Result<Metadata, AbiError> {
+    let mut fields = token.into_fields()?;
+
+    let mut version = fields.next_field()?.into_fields()?;
+    let start = version.next_field()?.into_u64()?;
+    let end = version.next_field()?.into_u64()?;
+    let epoch = fields.next_field()?.into_u64()?;
+    let verifier_list = fields
+        .next_field()?
+        .into_array()?
+        .into_iter()
+        .map(validator_from_token)
+        .collect::<Result<_, _>>()?;
+    let propose_counter = fields
+        .next_field()?
+        .into_array()?
+        .into_iter()
+        .map(propose_count_from_token)
+        .collect::<Result<_, _>>()?;
+    let consensus_config = consensus_config_from_token(fields.next_field()?)?;
+
+    Ok(Metadata {
+        version: MetadataVersion::new(start, end),
+        epoch,
+        verifier_list,
+        propose_counter,
+        consensus_config,
+    })
+}
+
+fn validator_from_token(token: Token) -> Result<ValidatorExtend, AbiError> {
+    let mut fields = token.into_fields()?;
+
+    Ok(ValidatorExtend {
+        bls_pub_key:    Hex::encode(fields.next_field()?.into_bytes()?),
+        pub_key:        Hex::encode(fields.next_field()?.into_bytes()?),
+        address:        fields.next_field()?.into_address()?,
+        propose_weight: fields.next_field()?.into_u32()?,
+        vote_weight:    fields.next_field()?.into_u32()?,
+    })
+}
+
+fn propose_count_from_token(token: Token) -> Result<ProposeCount, AbiError> {
+    let mut fields = token.into_fields()?;
+
+    Ok(ProposeCount {
+        address: fields.next_field()?.into_address()?,
+        count:   fields.next_field()?.into_u64()?,
+    })
+}
+
+fn consensus_config_from_token(token: Token) -> Result<ConsensusConfig, AbiError> {
+    let mut fields = token.into_fields()?;
+    let mut next = || fields.next_field()?.into_u64();
+
+    Ok(ConsensusConfig {
+        gas_limit:       next()?,
+        interval:        next()?,
+        propose_ratio:   next()?,
+        prevote_ratio:   next()?,
+        precommit_ratio: next()?,
+        brake_ratio:     next()?,
+        tx_num_limit:    next()?,
+        max_tx_size:     next()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use ethereum_types::H160;
+
+    use super::*;
+
+    fn metadata() -> Metadata {
+        let mut metadata: Metadata =
+            serde_json::from_str(include_str!("../../../examples/metadata.json")).unwrap();
+        // Not deserialized, so copied from the fixture.
+        metadata.propose_counter = vec![ProposeCount {
+            address: H160::zero(),
+            count:   0,
+        }];
+        metadata
+    }
+
+    #[test]
+    fn test_metadata_call() {
+        for call in [
+            MetadataCall::GetMetadata(1),
+            MetadataCall::GetMetadata(u64::MAX),
+        ] {
+            let calldata = call.encode();
+            assert_eq!(calldata.len(), 4 + 32);
+            assert_eq!(calldata[..4], abi::selector(call.signature()));
+            assert_eq!(MetadataCall::decode(&calldata).unwrap(), call);
+        }
+
+        assert_eq!(
+            crate::hex::hex_encode(MetadataCall::GetMetadata(1).encode()),
+            "998e84a30000000000000000000000000000000000000000000000000000000000000001"
+        );
+        assert_eq!(
+            MetadataCall::decode(&[0u8; 36]),
+            Err(AbiError::SelectorMismatch)
+        );
+    }
+
+    #[test]
+    fn test_decode_metadata() {
+        // `examples/metadata.json` encoded by hand following the Solidity ABI
+        // specification, not recorded from a node, so it only pins the codec.
+        let output =
+            crate::hex::hex_decode(include_str!("../../../examples/metadata_abi.txt").trim())
+                .unwrap();
+        let metadata = metadata();
+
+        assert_eq!(decode_metadata(&output).unwrap(), metadata);
+        assert_eq!(encode_metadata(&metadata), output);
+
+        assert!(decode_metadata(&output[..output.len() - 32]).is_err());
+        // The high bytes of the first address are not zero.
+        let mut dirty = output.clone();
+        let address = dirty
+            .windows(20)
+            .position(|w| w == metadata.verifier_list[0].address.as_bytes())
+            .unwrap();
+        dirty[address - 1] = 1;
+        assert!(matches!(
+            decode_metadata(&dirty),
+            Err(AbiError::InvalidValue(_))
+        ));
+    }
+
+    #[test]
+    fn test_decode_validator() {
+        let validator = ValidatorExtend {
+            address: H160::from([1u8; 20]),
+            propose_weight: 1,
+            vote_weight: u32::MAX,
+            ..Default::default()
+        };
+        let token = validator_token(&validator);
+        assert_eq!(validator_from_token(token).unwrap(), validator);
+
+        // A uint32 weight wider than 32 bits.
+        let token = Token::Tuple(vec![
+            Token::Bytes(Vec::new()),
+            Token::Bytes(Vec::new()),
+            Token::Address(H160::zero()),
+            Token::Uint(U256::from(u64::from(u32::MAX) + 1)),
+            uint(1),
+        ]);
+        assert_eq!(validator_from_token(token), Err(AbiError::UnexpectedToken));
+    }
+}
@@ -0,0 +1,9 @@
+//! Codecs for the system contracts of Axon, whose addresses are listed in
+//! [`consts`](crate::consts).
+
+pub mod abi;
+pub mod ckb_light_client;
+pub mod image_cell;
+pub mod metadata;
+
+pub use abi::AbiError;
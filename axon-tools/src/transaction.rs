@@ -0,0 +1,381 @@
+use alloc::vec::Vec;
+
+use ethereum_types::H256;
+
+use crate::proof::verify_trie_proof;
+use crate::types::{AxonBlock, UnverifiedTransaction};
+use crate::{keccak_256, Error};
+
+/// Verify that the `index`-th transaction of a block is stored under
+/// `block.header.transactions_root`, and return the decoded transaction.
+///
+/// The transactions trie is keyed by the RLP encoded transaction index, and
+/// holds the EIP-2718 envelope of each transaction. The keccak hash of the
+/// envelope must equal `block.tx_hashes[index]`, so the transaction is bound
+/// to the block proposal signed by the validators.
+pub fn verify_transaction_proof(
+    block: &AxonBlock,
+    index: u64,
+    proof: Vec<Vec<u8>>,
+) -> Result<UnverifiedTransaction, Error> {
+    let tx_hash = usize::try_from(index)
+        .ok()
+        .and_then(|i| block.tx_hashes.get(i))
+        .ok_or(Error::TransactionNotFound(index))?;
+
+    let raw = verify_trie_proof(block.header.transactions_root, &rlp::encode(&index), proof)?
+        .ok_or(Error::VerifyMptProof)?;
+
+    if H256(keccak_256(&raw)) != *tx_hash {
+        return Err(Error::TransactionHashMismatch(index));
+    }
+
+    Ok(UnverifiedTransaction::decode(&raw)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use ethereum_types::{H160, U256};
+
+    use crate::prover::TrieProver;
+    use crate::types::{
+        AccessListItem, AddressSource, CellDep, Eip1559Transaction, Eip2930Transaction,
+        InteroperationCells, InteroperationSignature, LegacyTransaction, OutPoint,
+        SignatureComponents, SignatureR, SignatureS, TransactionAction, TransactionSignature,
+        TypesError, UnsignedTransaction, Witness,
+    };
+
+    use super::*;
+
+    fn signature(v: u64) -> SignatureComponents {
+        TransactionSignature {
+            v,
+            r: U256::from(1),
+            s: U256::from(2),
+        }
+        .into()
+    }
+
+    fn interoperation_signature() -> InteroperationSignature {
+        InteroperationSignature {
+            v: 0,
+            r: SignatureR::ByRef(InteroperationCells {
+                cell_deps:      vec![CellDep {
+                    out_point: OutPoint {
+                        tx_hash: H256::from([5u8; 32]),
+                        index:   1,
+                    },
+                    dep_type:  0,
+                }],
+                header_deps:    Vec::new(),
+                out_points:     vec![OutPoint {
+                    tx_hash: H256::from([6u8; 32]),
+                    index:   0,
+                }],
+                address_source: AddressSource { type_: 0, index: 0 },
+            }),
+            s: SignatureS {
+                witnesses: vec![Witness {
+                    lock: Bytes::from(vec![7u8; 65]),
+                    ..Default::default()
+                }],
+            },
+        }
+    }
+
+    fn transactions() -> Vec<UnverifiedTransaction> {
+        let access_list = vec![AccessListItem {
+            address:      H160::from([1u8; 20]),
+            storage_keys: vec![H256::from([2u8; 32])],
+        }];
+
+        vec![
+            UnverifiedTransaction {
+                transaction: UnsignedTransaction::Legacy(LegacyTransaction {
+                    nonce:     U256::zero(),
+                    gas_price: U256::from(1337),
+                    gas_limit: U256::from(21000),
+                    action:    TransactionAction::Create,
+                    value:     U256::zero(),
+                    data:      Bytes::from(vec![0x60u8, 0x80]),
+                }),
+                signature:   signature(27),
+            },
+            UnverifiedTransaction {
+                transaction: UnsignedTransaction::Eip2930(Eip2930Transaction {
+                    chain_id:    2022,
+                    nonce:       U256::from(1),
+                    gas_price:   U256::from(1337),
+                    gas_limit:   U256::from(50000),
+                    action:      TransactionAction::Call(H160::from([3u8; 20])),
+                    value:       U256::from(10),
+                    data:        Bytes::new(),
+                    access_list: access_list.clone(),
+                }),
+                signature:   signature(0),
+            },
+            UnverifiedTransaction {
+                transaction: UnsignedTransaction::Eip1559(Eip1559Transaction {
+                    chain_id: 2022,
+                    nonce: U256::from(2),
+                    max_priority_fee_per_gas: U256::from(1),
+                    max_fee_per_gas: U256::from(1337),
+                    gas_limit: U256::from(50000),
+                    action: TransactionAction::Call(H160::from([4u8; 20])),
+                    value: U256::zero(),
+                    data: Bytes::from(vec![0xa9u8, 0x05, 0x9c, 0xbb]),
+                    access_list,
+                }),
+                signature:   signature(1),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_transaction_codec() {
+        for tx in transactions() {
+            let raw = tx.encode();
+            assert_eq!(UnverifiedTransaction::decode(&raw).unwrap(), tx);
+        }
+
+        // The EIP-155 example transaction.
+        let raw = crate::hex::hex_decode(
+            "f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83",
+        )
+        .unwrap();
+        let tx = UnverifiedTransaction::decode(&raw).unwrap();
+        match &tx.transaction {
+            UnsignedTransaction::Legacy(legacy) => {
+                assert_eq!(legacy.nonce, U256::from(9));
+                assert_eq!(legacy.gas_price, U256::from(20_000_000_000u64));
+                assert_eq!(
+                    legacy.action,
+                    TransactionAction::Call(H160::from([0x35u8; 20]))
+                );
+                assert_eq!(legacy.value, U256::exp10(18));
+            }
+            _ => panic!("not a legacy transaction"),
+        }
+        assert_eq!(tx.signature.v(), 37);
+        assert_eq!(tx.encode().as_ref(), raw.as_slice());
+
+        assert!(UnverifiedTransaction::decode(&[]).is_err());
+        assert!(UnverifiedTransaction::decode(&[3u8, 0xc0]).is_err());
+        let typed_legacy = [&[0u8][..], &raw].concat();
+        assert!(UnverifiedTransaction::decode(&typed_legacy).is_err());
+        assert!(UnverifiedTransaction::decode(&raw[..raw.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_transaction_serde() {
+        for tx in transactions() {
+            let json = serde_json::to_value(&tx).unwrap();
+            assert_eq!(json["type"], format!("0x{}", tx.transaction.tx_type()));
+            assert_eq!(
+                serde_json::from_value::<UnverifiedTransaction>(json).unwrap(),
+                tx
+            );
+        }
+
+        let json = serde_json::to_value(&transactions()[0]).unwrap();
+        assert_eq!(json["to"], serde_json::Value::Null);
+        assert_eq!(json["input"], "0x6080");
+        assert_eq!(json["v"], "0x1b");
+    }
+
+    #[cfg(feature = "secp256k1")]
+    #[test]
+    fn test_recover_sender() {
+        use k256::ecdsa::SigningKey;
+
+        use crate::types::SignedTransaction;
+
+        // The EIP-155 example transaction, signed by the private key
+        // 0x4646...46.
+        let raw = crate::hex::hex_decode(
+            "f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83",
+        )
+        .unwrap();
+        let tx = UnverifiedTransaction::decode(&raw).unwrap();
+        assert_eq!(tx.chain_id(), Some(1));
+        assert_eq!(
+            tx.signature_hash(),
+            serde_json::from_str::<H256>(
+                "\"0xdaf5a779ae972f972197303d7b574746c7ef83eadac0f2791ad23db92e4c8e53\""
+            )
+            .unwrap()
+        );
+        let sender =
+            serde_json::from_str::<H160>("\"0x9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f\"").unwrap();
+        let signed = SignedTransaction::try_from(tx.clone()).unwrap();
+        assert_eq!(signed.sender, sender);
+        assert_eq!(signed.hash(), H256(keccak_256(&raw)));
+
+        let key = SigningKey::from_slice(&[0x46u8; 32]).unwrap();
+        for mut tx in transactions().into_iter().skip(1) {
+            let (signature, recovery_id) = key
+                .sign_prehash_recoverable(tx.signature_hash().as_bytes())
+                .unwrap();
+            let (r, s) = signature.split_bytes();
+            let mut signature = TransactionSignature {
+                v: recovery_id.to_byte().into(),
+                r: U256::from_big_endian(&r),
+                s: U256::from_big_endian(&s),
+            };
+            tx.signature = signature.clone().into();
+
+            let signed = SignedTransaction::try_from(tx.clone()).unwrap();
+            assert_eq!(signed.sender, sender);
+
+            // The sender is recovered again instead of read from the JSON.
+            let mut json = serde_json::to_value(&signed).unwrap();
+            json["from"] = serde_json::to_value(H160::zero()).unwrap();
+            assert_eq!(
+                serde_json::from_value::<SignedTransaction>(json).unwrap(),
+                signed
+            );
+
+            signature.v = 27;
+            tx.signature = signature.clone().into();
+            assert!(matches!(
+                SignedTransaction::try_from(tx.clone()),
+                Err(TypesError::InvalidSignatureV(27))
+            ));
+            signature.v = recovery_id.to_byte().into();
+
+            // The same signature with a high `s` is rejected since EIP-2.
+            let order = U256::from_str_radix(
+                "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141",
+                16,
+            )
+            .unwrap();
+            let low_s = signature.s;
+            signature.s = order - low_s;
+            tx.signature = signature.clone().into();
+            assert!(matches!(
+                SignedTransaction::try_from(tx.clone()),
+                Err(TypesError::InvalidSignature)
+            ));
+            signature.s = low_s;
+            signature.r = order;
+            tx.signature = signature.clone().into();
+            assert!(matches!(
+                SignedTransaction::try_from(tx.clone()),
+                Err(TypesError::InvalidSignature)
+            ));
+            signature.r = U256::zero();
+            tx.signature = signature.into();
+            assert!(matches!(
+                SignedTransaction::try_from(tx),
+                Err(TypesError::MissingSignature)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_interoperation_transaction() {
+        let mut tx = transactions().remove(2);
+        tx.signature = interoperation_signature().into();
+
+        let raw = tx.encode();
+        assert_eq!(UnverifiedTransaction::decode(&raw).unwrap(), tx);
+        let json = serde_json::to_value(&tx).unwrap();
+        assert_eq!(
+            serde_json::from_value::<UnverifiedTransaction>(json).unwrap(),
+            tx
+        );
+
+        let script_hash = H256::from([8u8; 32]);
+        let sender = InteroperationSignature::sender(&script_hash);
+        assert_eq!(sender.as_bytes(), &keccak_256(script_hash.as_bytes())[12..]);
+
+        #[cfg(feature = "secp256k1")]
+        {
+            use crate::types::SignedTransaction;
+
+            assert!(matches!(
+                SignedTransaction::try_from(tx.clone()),
+                Err(TypesError::MissingInteroperationSender)
+            ));
+            let signed = SignedTransaction::from_interoperation(tx, &script_hash).unwrap();
+            assert_eq!(signed.sender, sender);
+            assert!(signed.public.is_none());
+        }
+
+        let r = interoperation_signature().r.encode();
+        assert_eq!(r[0], 0);
+        assert!(matches!(
+            SignatureR::decode(&[]),
+            Err(TypesError::SignatureRIsEmpty)
+        ));
+        let mut by_other = r.to_vec();
+        by_other[0] = 2;
+        assert!(matches!(
+            SignatureR::decode(&by_other),
+            Err(TypesError::InvalidSignatureRType)
+        ));
+
+        let mut signature = interoperation_signature();
+        if let SignatureR::ByRef(cells) = &mut signature.r {
+            cells.address_source.type_ = 2;
+        }
+        assert!(matches!(
+            SignatureR::decode(&signature.r.encode()),
+            Err(TypesError::InvalidAddressSourceType)
+        ));
+
+        // A signature by reference without cell deps encodes in less than 32
+        // bytes, like an ECDSA `r`.
+        let mut signature = interoperation_signature();
+        if let SignatureR::ByRef(cells) = &mut signature.r {
+            cells.cell_deps.clear();
+            cells.out_points.clear();
+        }
+        assert!(signature.r.encode().len() <= 32);
+        assert!(matches!(
+            SignatureR::decode(&signature.r.encode()),
+            Err(TypesError::MissingInteroperationCell)
+        ));
+        let mut tx = transactions().remove(2);
+        tx.signature = signature.into();
+        assert!(UnverifiedTransaction::decode(&tx.encode()).is_err());
+    }
+
+    #[test]
+    fn test_verify_transaction_proof() {
+        let txs = transactions();
+        let mut block: AxonBlock =
+            serde_json::from_str(include_str!("../../examples/block.json")).unwrap();
+        let prover = TrieProver::from_transactions(&txs).unwrap();
+        block.tx_hashes = txs
+            .iter()
+            .map(|tx| H256(keccak_256(&tx.encode())))
+            .collect();
+        block.header.transactions_root = prover.root();
+
+        for (i, tx) in txs.iter().enumerate() {
+            let proof = prover.generate_proof(i as u64).unwrap();
+            assert_eq!(
+                verify_transaction_proof(&block, i as u64, proof).unwrap(),
+                *tx
+            );
+        }
+
+        let proof = prover.generate_proof(1).unwrap();
+        assert!(matches!(
+            verify_transaction_proof(&block, 3, proof.clone()),
+            Err(Error::TransactionNotFound(3))
+        ));
+        assert!(matches!(
+            verify_transaction_proof(&block, 2, proof.clone()),
+            Err(Error::VerifyMptProof)
+        ));
+
+        block.tx_hashes.swap(0, 1);
+        assert!(matches!(
+            verify_transaction_proof(&block, 1, proof),
+            Err(Error::TransactionHashMismatch(1))
+        ));
+    }
+}
@@ -0,0 +1,193 @@
+use alloc::vec::Vec;
+
+use ethereum_types::H256;
+use rlp::{Prototype, Rlp};
+
+use crate::{error::Error, keccak_256};
+
+const HASH_LEN: usize = 32;
+
+/// The nodes of a Merkle Patricia proof together with their hashes, from which
+/// keys are read by walking down from the root.
+///
+/// Nodes shorter than 32 bytes are embedded in their parent, so only the root
+/// and the nodes of at least 32 bytes are looked up by hash. A hashed node on
+/// the path that is missing from the proof is an error, so a key is reported
+/// absent only when the path diverges or ends on an empty slot. The riscv crate
+/// verifies proofs the same way.
+pub(crate) struct NodeSet<'a, T> {
+    root:   H256,
+    nodes:  &'a [T],
+    hashes: Vec<[u8; HASH_LEN]>,
+}
+
+impl<'a, T: AsRef<[u8]>> NodeSet<'a, T> {
+    pub(crate) fn new(root: H256, nodes: &'a [T]) -> Self {
+        let hashes = nodes.iter().map(|node| keccak_256(node.as_ref())).collect();
+
+        NodeSet {
+            root,
+            nodes,
+            hashes,
+        }
+    }
+
+    fn lookup(&self, hash: &[u8]) -> Option<&'a [u8]> {
+        self.nodes
+            .iter()
+            .zip(self.hashes.iter())
+            .find(|(node, h)| {
+                h[..] == *hash && (node.as_ref().len() >= HASH_LEN || **h == self.root.0)
+            })
+            .map(|(node, _)| node.as_ref())
+    }
+
+    pub(crate) fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+        let mut node = Rlp::new(self.lookup(&self.root.0).ok_or(Error::VerifyMptProof)?);
+        check_node(&node)?;
+
+        let key = Nibbles::Raw(key);
+        let mut pos = 0usize;
+
+        loop {
+            match node.prototype().map_err(|_| Error::VerifyMptProof)? {
+                Prototype::Data(0) => return Ok(None),
+                Prototype::Data(HASH_LEN) => {
+                    let raw = self
+                        .lookup(node.data().map_err(|_| Error::VerifyMptProof)?)
+                        .ok_or(Error::VerifyMptProof)?;
+                    node = Rlp::new(raw);
+                    check_node(&node)?;
+                }
+                Prototype::List(2) => {
+                    let path = node
+                        .at(0)
+                        .and_then(|r| r.data())
+                        .map_err(|_| Error::VerifyMptProof)?;
+                    let (path, is_leaf) = Nibbles::from_compact(path)?;
+
+                    if is_leaf {
+                        if key.len() - pos != path.len() || !key.starts_with_at(pos, &path) {
+                            return Ok(None);
+                        }
+                        let value = node
+                            .at(1)
+                            .and_then(|r| r.data())
+                            .map_err(|_| Error::VerifyMptProof)?;
+                        return Ok(Some(value.to_vec()));
+                    }
+
+                    if !key.starts_with_at(pos, &path) {
+                        return Ok(None);
+                    }
+                    pos += path.len();
+                    node = node.at(1).map_err(|_| Error::VerifyMptProof)?;
+                }
+                Prototype::List(17) => {
+                    if pos == key.len() {
+                        let value = node.at(16).map_err(|_| Error::VerifyMptProof)?;
+                        if value.is_empty() {
+                            return Ok(None);
+                        }
+                        return Ok(Some(
+                            value.data().map_err(|_| Error::VerifyMptProof)?.to_vec(),
+                        ));
+                    }
+
+                    node = node.at(key.at(pos)).map_err(|_| Error::VerifyMptProof)?;
+                    pos += 1;
+                }
+                _ => return Err(Error::VerifyMptProof),
+            }
+        }
+    }
+}
+
+/// Check that a node and the nodes embedded in it are well formed, the same
+/// way `cita_trie` checks a node when it decodes it.
+fn check_node(node: &Rlp) -> Result<(), Error> {
+    match node.prototype().map_err(|_| Error::VerifyMptProof)? {
+        Prototype::Data(0) | Prototype::Data(HASH_LEN) => Ok(()),
+        Prototype::List(2) => {
+            let path = node
+                .at(0)
+                .and_then(|r| r.data())
+                .map_err(|_| Error::VerifyMptProof)?;
+            let (_, is_leaf) = Nibbles::from_compact(path)?;
+            let child = node.at(1).map_err(|_| Error::VerifyMptProof)?;
+
+            if is_leaf {
+                child.data().map_err(|_| Error::VerifyMptProof)?;
+                Ok(())
+            } else {
+                check_node(&child)
+            }
+        }
+        Prototype::List(17) => {
+            for i in 0..16 {
+                check_node(&node.at(i).map_err(|_| Error::VerifyMptProof)?)?;
+            }
+
+            let value = node.at(16).map_err(|_| Error::VerifyMptProof)?;
+            if !value.is_empty() {
+                value.data().map_err(|_| Error::VerifyMptProof)?;
+            }
+            Ok(())
+        }
+        _ => Err(Error::VerifyMptProof),
+    }
+}
+
+/// A borrowed nibble path, without the leaf terminator.
+enum Nibbles<'a> {
+    /// Every byte holds two nibbles.
+    Raw(&'a [u8]),
+    /// The first nibble is the low half of the compact flag byte.
+    Odd(u8, &'a [u8]),
+}
+
+impl<'a> Nibbles<'a> {
+    /// Decode a hex prefix encoded path, and tell whether it is a leaf path.
+    fn from_compact(compact: &'a [u8]) -> Result<(Self, bool), Error> {
+        let (flag, rest) = compact.split_first().ok_or(Error::VerifyMptProof)?;
+        let is_leaf = match flag >> 4 {
+            0x0 | 0x1 => false,
+            0x2 | 0x3 => true,
+            _ => return Err(Error::VerifyMptProof),
+        };
+
+        if flag >> 4 & 1 == 1 {
+            Ok((Nibbles::Odd(flag & 0x0f, rest), is_leaf))
+        } else {
+            Ok((Nibbles::Raw(rest), is_leaf))
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Nibbles::Raw(bytes) => bytes.len() * 2,
+            Nibbles::Odd(_, bytes) => bytes.len() * 2 + 1,
+        }
+    }
+
+    fn at(&self, i: usize) -> usize {
+        let (bytes, i) = match self {
+            Nibbles::Raw(bytes) => (bytes, i),
+            Nibbles::Odd(first, _) if i == 0 => return *first as usize,
+            Nibbles::Odd(_, bytes) => (bytes, i - 1),
+        };
+
+        let byte = bytes[i / 2];
+        if i % 2 == 0 {
+            (byte >> 4) as usize
+        } else {
+            (byte & 0x0f) as usize
+        }
+    }
+
+    /// Whether the nibbles from `pos` on start with `prefix`.
+    fn starts_with_at(&self, pos: usize, prefix: &Nibbles) -> bool {
+        self.len() - pos >= prefix.len()
+            && (0..prefix.len()).all(|i| self.at(pos + i) == prefix.at(i))
+    }
+}
@@ -0,0 +1,324 @@
+use alloc::vec::Vec;
+
+use blst::min_pk::{PublicKey, Signature};
+use blst::{blst_scalar, BLST_ERROR};
+use ethereum_types::H256;
+
+use crate::proof::{prepare_sorted, verify_sorted, QuorumRule, DST};
+use crate::report::{VerificationReport, VerificationStage};
+use crate::types::{AxonBlock, Metadata, Proof, Proposal, ValidatorExtend};
+use crate::Error;
+
+/// The bits of the random scalar each signature of a batch is multiplied by.
+const BATCH_RAND_BITS: usize = 128;
+
+/// The first proof of a batch that fails to verify.
+#[derive(Debug)]
+pub struct BatchError {
+    /// The position of the offending proof in the batch.
+    pub index:  usize,
+    pub report: Box<VerificationReport>,
+}
+
+/// An immutable validator set for verifying many proofs of the same epoch.
+///
+/// The validators are sorted once, and their BLS public keys are decoded and
+/// subgroup checked once, when the set is built.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValidatorSet {
+    validators: Vec<ValidatorExtend>,
+    pub_keys:   Vec<PublicKey>,
+}
+
+impl ValidatorSet {
+    pub fn new(mut validator_list: Vec<ValidatorExtend>) -> Result<Self, Error> {
+        validator_list.sort();
+
+        let pub_keys = validator_list
+            .iter()
+            .map(|v| PublicKey::key_validate(v.bls_pub_key.as_ref()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(ValidatorSet {
+            validators: validator_list,
+            pub_keys,
+        })
+    }
+
+    /// The validators sorted in the order of the proof bitmap.
+    pub fn validators(&self) -> &[ValidatorExtend] {
+        &self.validators
+    }
+
+    pub fn len(&self) -> usize {
+        self.validators.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.validators.is_empty()
+    }
+
+    /// Verify the proof of a block with the vote weight based quorum rule.
+    pub fn verify_proof(
+        &self,
+        block: AxonBlock,
+        previous_state_root: H256,
+        proof: Proof,
+    ) -> Result<(), Error> {
+        self.verify_proof_with_report(block, previous_state_root, proof, QuorumRule::VoteWeight)
+            .into_result()
+    }
+
+    /// Verify the proof of a proposal with the vote weight based quorum rule.
+    pub fn verify_proof_by_proposal(&self, proposal: &Proposal, proof: Proof) -> Result<(), Error> {
+        verify_sorted(
+            proposal.hash(),
+            &self.validators,
+            Some(&self.pub_keys),
+            proof,
+            QuorumRule::VoteWeight,
+        )
+        .into_result()
+    }
+
+    /// Verify the proof of a block and report the signers, the weights and the
+    /// failed check if any.
+    pub fn verify_proof_with_report(
+        &self,
+        block: AxonBlock,
+        previous_state_root: H256,
+        proof: Proof,
+        quorum: QuorumRule,
+    ) -> VerificationReport {
+        verify_sorted(
+            Proposal::from((block, previous_state_root)).hash(),
+            &self.validators,
+            Some(&self.pub_keys),
+            proof,
+            quorum,
+        )
+    }
+
+    /// Verify the proofs of many blocks signed by this validator set with a
+    /// single multi-pairing.
+    ///
+    /// Each item is a block, the state root before it and its proof. The cheap
+    /// checks run for every proof first, then all the signatures are verified
+    /// together as a random linear combination. If the combined check fails,
+    /// the signatures are checked one by one to find the offender.
+    pub fn verify_proof_batch(
+        &self,
+        items: Vec<(AxonBlock, H256, Proof)>,
+        quorum: QuorumRule,
+    ) -> Result<(), BatchError> {
+        let mut prepared = Vec::with_capacity(items.len());
+        for (index, (block, previous_state_root, proof)) in items.into_iter().enumerate() {
+            let (report, signed) = prepare_sorted(
+                Proposal::from((block, previous_state_root)).hash(),
+                &self.validators,
+                Some(&self.pub_keys),
+                &proof,
+                quorum,
+            );
+            match signed {
+                Some(signed) => prepared.push((report, signed)),
+                None => {
+                    return Err(BatchError {
+                        index,
+                        report: Box::new(report),
+                    })
+                }
+            }
+        }
+
+        if prepared.is_empty() {
+            return Ok(());
+        }
+
+        let signed = prepared.iter().map(|(_, s)| s).collect::<Vec<_>>();
+        let msgs = signed
+            .iter()
+            .map(|s| s.hash_vote.as_slice())
+            .collect::<Vec<_>>();
+        let pks = signed.iter().map(|s| &s.pub_key).collect::<Vec<_>>();
+        let sigs = signed.iter().map(|s| &s.signature).collect::<Vec<_>>();
+        // Without an RNG, the signatures are only checked one by one.
+        if let Some(scalars) = batch_scalars(signed.len()) {
+            let res = Signature::verify_multiple_aggregate_signatures(
+                &msgs,
+                DST.as_bytes(),
+                &pks,
+                false,
+                &sigs,
+                true,
+                &scalars,
+                BATCH_RAND_BITS,
+            );
+            if res == BLST_ERROR::BLST_SUCCESS {
+                return Ok(());
+            }
+        }
+
+        for (index, (report, signed)) in prepared.into_iter().enumerate() {
+            let res = signed.signature.verify(
+                true,
+                &signed.hash_vote,
+                DST.as_bytes(),
+                &[],
+                &signed.pub_key,
+                false,
+            );
+            if res != BLST_ERROR::BLST_SUCCESS {
+                return Err(BatchError {
+                    index,
+                    report: Box::new(report.fail(VerificationStage::Signature, res.into())),
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Draw the non-zero random scalars of a batch from the OS RNG, or `None` if
+/// it fails.
+///
+/// The scalars must be unknown to whoever made the signatures, otherwise
+/// invalid signatures can be crafted to cancel each other out.
+fn batch_scalars(len: usize) -> Option<Vec<blst_scalar>> {
+    let mut bytes = vec![0u8; len * BATCH_RAND_BITS / 8];
+    getrandom::getrandom(&mut bytes).ok()?;
+
+    let scalars = bytes
+        .chunks(BATCH_RAND_BITS / 8)
+        .map(|chunk| {
+            let mut scalar = blst_scalar::default();
+            scalar.b[..chunk.len()].copy_from_slice(chunk);
+            if scalar.b.iter().all(|b| *b == 0) {
+                scalar.b[0] = 1;
+            }
+            scalar
+        })
+        .collect();
+    Some(scalars)
+}
+
+impl TryFrom<&Metadata> for ValidatorSet {
+    type Error = Error;
+
+    fn try_from(metadata: &Metadata) -> Result<Self, Self::Error> {
+        ValidatorSet::new(metadata.verifier_list.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::Hex;
+
+    use super::*;
+
+    #[test]
+    fn test_reject_invalid_key() {
+        let metadata: Metadata =
+            serde_json::from_str(include_str!("../../examples/metadata.json")).unwrap();
+        let set = ValidatorSet::try_from(&metadata).unwrap();
+        assert_eq!(set.len(), metadata.verifier_list.len());
+        assert!(set.validators().windows(2).all(|w| w[0] <= w[1]));
+
+        let mut validator_list = metadata.verifier_list;
+        validator_list[0].bls_pub_key = Hex::encode([0u8; 48]);
+        assert!(matches!(
+            ValidatorSet::new(validator_list),
+            Err(Error::Bls(_))
+        ));
+    }
+
+    #[cfg(feature = "sign")]
+    fn validators() -> (Vec<blst::min_pk::SecretKey>, Vec<ValidatorExtend>) {
+        (1..=4u8)
+            .map(|i| {
+                let sk = blst::min_pk::SecretKey::key_gen(&[i; 32], &[]).unwrap();
+                let validator = ValidatorExtend {
+                    bls_pub_key: Hex::encode(sk.sk_to_pk().to_bytes()),
+                    pub_key: Hex::encode([i; 33]),
+                    vote_weight: 1,
+                    ..Default::default()
+                };
+                (sk, validator)
+            })
+            .unzip()
+    }
+
+    #[cfg(feature = "sign")]
+    #[test]
+    fn test_verify_with_validator_set() {
+        use crate::sign::build_proof;
+
+        let block: AxonBlock =
+            serde_json::from_str(include_str!("../../examples/block.json")).unwrap();
+        let (sks, mut validator_list) = validators();
+        let set = ValidatorSet::new(validator_list.clone()).unwrap();
+
+        let hash = block.hash(H256::zero());
+        let proof =
+            build_proof(&mut validator_list, &sks[..3], block.header.number, 0, hash).unwrap();
+        assert!(set
+            .verify_proof(block.clone(), H256::zero(), proof.clone())
+            .is_ok());
+        assert!(set
+            .verify_proof(block, H256::from([1u8; 32]), proof)
+            .is_err());
+    }
+
+    #[cfg(feature = "sign")]
+    #[test]
+    fn test_verify_proof_batch() {
+        use crate::sign::build_proof;
+
+        let block: AxonBlock =
+            serde_json::from_str(include_str!("../../examples/block.json")).unwrap();
+        let (sks, mut validator_list) = validators();
+        let set = ValidatorSet::new(validator_list.clone()).unwrap();
+
+        let mut items = (0..4u8)
+            .map(|i| {
+                let mut block = block.clone();
+                block.header.number += u64::from(i);
+                let previous_state_root = H256::from([i; 32]);
+                let hash = block.hash(previous_state_root);
+                let proof =
+                    build_proof(&mut validator_list, &sks, block.header.number, 0, hash).unwrap();
+                (block, previous_state_root, proof)
+            })
+            .collect::<Vec<_>>();
+        assert!(set
+            .verify_proof_batch(items.clone(), QuorumRule::VoteWeight)
+            .is_ok());
+        assert!(set
+            .verify_proof_batch(Vec::new(), QuorumRule::VoteWeight)
+            .is_ok());
+
+        // Each signature is valid on its own but not for the proof it is in.
+        let signature = items[1].2.signature.clone();
+        items[1].2.signature = items[2].2.signature.clone();
+        items[2].2.signature = signature;
+        let err = set
+            .verify_proof_batch(items.clone(), QuorumRule::VoteWeight)
+            .unwrap_err();
+        assert_eq!(err.index, 1);
+        assert_eq!(
+            err.report.failure.unwrap().stage,
+            VerificationStage::Signature
+        );
+
+        items[3].1 = H256::zero();
+        let err = set
+            .verify_proof_batch(items, QuorumRule::VoteWeight)
+            .unwrap_err();
+        assert_eq!(err.index, 3);
+        assert_eq!(
+            err.report.failure.unwrap().stage,
+            VerificationStage::BlockHash
+        );
+    }
+}